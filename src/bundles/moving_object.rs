@@ -2,6 +2,7 @@ use bevy::prelude::*;
 
 use crate::plugins::{
     collision_detection::Collider,
+    interpolation::TransformInterpolation,
     movement::{Acceleration, Velocity},
     rotation::RotationVelocity,
 };
@@ -13,4 +14,5 @@ pub struct MovingObjectBundle {
     pub rotation_velocity: RotationVelocity,
     pub collider: Collider,
     pub model: SceneBundle,
+    pub interpolation: TransformInterpolation,
 }
@@ -3,10 +3,19 @@ use bevy::prelude::*;
 #[derive(Component, Debug)]
 pub struct Health {
     pub value: f32,
+    /// Who dealt the most recent damage, credited with the kill.
+    pub last_attacker: Option<Entity>,
 }
 
 impl Health {
     pub fn new(value: f32) -> Self {
-        Self { value }
+        Self {
+            value,
+            last_attacker: None,
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.value <= 0.0
     }
 }
@@ -4,17 +4,24 @@ mod health;
 mod plugins;
 mod resources;
 mod state;
+#[cfg(test)]
+mod test_utils;
 
 // third party
 use bevy::prelude::*;
 
 // project internal
 use plugins::{
-    asteroids::AsteroidPlugin, camera::CameraPlugin, collision_detection::CollisionDetectionPlugin,
-    debug::DebugPlugin, despawn::DespawnPlugin, movement::MovementPlugin, rotation::RotationPlugin,
-    schedule::SchedulePlugin, spaceship::SpaceshipPlugin,
+    actions::ActionsPlugin, asteroids::AsteroidPlugin, camera::CameraPlugin,
+    collision_detection::CollisionDetectionPlugin, demage::DemagePlugin, despawn::DespawnPlugin,
+    game_over::GameOverPlugin, homing::HomingPlugin, hud::HudPlugin,
+    interpolation::InterpolationPlugin, menu::MenuPlugin, movement::MovementPlugin,
+    physics::PhysicsPlugin, pickups::PickupPlugin, playfield::PlayfieldPlugin,
+    replay::ReplayPlugin, rotation::RotationPlugin, schedule::SchedulePlugin, score::ScorePlugin,
+    shield::ShieldPlugin, spaceship::SpaceshipPlugin, ufo::UfoPlugin, waves::WavesPlugin,
+    weapon::WeaponPlugin,
 };
-use resources::asset_loader::AssetLoaderPlugin;
+use resources::{asset_loader::AssetLoaderPlugin, game_config::GameConfigPlugin, rng::RngPlugin};
 use state::StatePlugin;
 
 fn main() {
@@ -28,15 +35,33 @@ fn main() {
         .add_plugins(DefaultPlugins)
         // User configured plugins.
         .add_plugins(AssetLoaderPlugin)
+        .add_plugins(ActionsPlugin)
+        .add_plugins(GameConfigPlugin)
+        .add_plugins(RngPlugin)
         .add_plugins(SpaceshipPlugin)
         .add_plugins(AsteroidPlugin)
+        .add_plugins(UfoPlugin)
+        .add_plugins(PickupPlugin)
+        .add_plugins(WavesPlugin)
         .add_plugins(MovementPlugin)
+        .add_plugins(InterpolationPlugin)
+        .add_plugins(PlayfieldPlugin)
         .add_plugins(RotationPlugin)
         .add_plugins(CameraPlugin)
         .add_plugins(CollisionDetectionPlugin)
+        .add_plugins(DemagePlugin)
+        .add_plugins(PhysicsPlugin)
+        .add_plugins(ShieldPlugin)
+        .add_plugins(WeaponPlugin)
+        .add_plugins(HomingPlugin)
+        .add_plugins(ScorePlugin)
         .add_plugins(DespawnPlugin)
+        .add_plugins(GameOverPlugin)
+        .add_plugins(HudPlugin)
+        .add_plugins(MenuPlugin)
         .add_plugins(SchedulePlugin)
         .add_plugins(StatePlugin)
-        // .add_plugins(DebugPlugin)
+        .add_plugins(ReplayPlugin)
+        // .add_plugins(plugins::debug::DebugPlugin)
         .run();
 }
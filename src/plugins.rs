@@ -1,9 +1,26 @@
+pub mod actions;
 pub mod asteroids;
 pub mod camera;
 pub mod collision_detection;
+#[allow(dead_code)]
 pub mod debug;
+pub mod demage;
 pub mod despawn;
+pub mod game_over;
+pub mod homing;
+pub mod hud;
+pub mod interpolation;
+pub mod menu;
 pub mod movement;
+pub mod physics;
+pub mod pickups;
+pub mod playfield;
+pub mod replay;
 pub mod rotation;
 pub mod schedule;
+pub mod score;
+pub mod shield;
 pub mod spaceship;
+pub mod ufo;
+pub mod waves;
+pub mod weapon;
@@ -0,0 +1,314 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+};
+
+use bevy::{input::InputSystem, prelude::*};
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+const BINDINGS_PATH: &str = "bindings.ron";
+const DEFAULT_DEAD_ZONE: f32 = 0.15;
+
+/// A logical button the game reacts to, independent of the device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    Fire,
+    Shield,
+    /// Slows the ship down in the Newtonian flight model.
+    Brake,
+    NextWeapon,
+    /// Picks the weapon at this index in the config's weapon list.
+    SelectWeapon(usize),
+    Pause,
+    Confirm,
+    Back,
+    MenuUp,
+    MenuDown,
+}
+
+/// A logical control that ranges from -1 to 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ActionAxis {
+    /// Forward is positive.
+    Thrust,
+    /// Right is positive.
+    Turn,
+    Roll,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    GamepadButton(GamepadButtonType),
+}
+
+/// Buttons push an axis fully one way; sticks move it proportionally.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AxisBinding {
+    #[serde(default)]
+    pub positive: Vec<InputBinding>,
+    #[serde(default)]
+    pub negative: Vec<InputBinding>,
+    #[serde(default)]
+    pub gamepad_axes: Vec<GamepadAxisType>,
+}
+
+/// Maps devices to actions. Loaded from `bindings.ron` in the working
+/// directory, which is created with the defaults on first start.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct Bindings {
+    /// Stick deflection below this is treated as centered.
+    pub dead_zone: f32,
+    pub actions: BTreeMap<Action, Vec<InputBinding>>,
+    pub axes: BTreeMap<ActionAxis, AxisBinding>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        use GamepadButtonType as Button;
+        use InputBinding::{GamepadButton, Key};
+
+        let mut actions = BTreeMap::from([
+            (
+                Action::Fire,
+                vec![Key(KeyCode::Space), GamepadButton(Button::RightTrigger2)],
+            ),
+            (
+                Action::Shield,
+                vec![Key(KeyCode::Tab), GamepadButton(Button::LeftTrigger2)],
+            ),
+            (
+                Action::Brake,
+                vec![Key(KeyCode::ShiftLeft), GamepadButton(Button::East)],
+            ),
+            (
+                Action::NextWeapon,
+                vec![GamepadButton(Button::RightTrigger)],
+            ),
+            (
+                Action::Pause,
+                vec![Key(KeyCode::Escape), GamepadButton(Button::Start)],
+            ),
+            (
+                Action::Confirm,
+                vec![Key(KeyCode::Return), GamepadButton(Button::South)],
+            ),
+            (
+                Action::Back,
+                vec![Key(KeyCode::Escape), GamepadButton(Button::East)],
+            ),
+            (
+                Action::MenuUp,
+                vec![Key(KeyCode::Up), GamepadButton(Button::DPadUp)],
+            ),
+            (
+                Action::MenuDown,
+                vec![Key(KeyCode::Down), GamepadButton(Button::DPadDown)],
+            ),
+        ]);
+        let weapon_keys = [
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+            KeyCode::Key5,
+            KeyCode::Key6,
+            KeyCode::Key7,
+            KeyCode::Key8,
+            KeyCode::Key9,
+        ];
+        for (index, key) in weapon_keys.into_iter().enumerate() {
+            actions.insert(Action::SelectWeapon(index), vec![Key(key)]);
+        }
+
+        let axes = BTreeMap::from([
+            (
+                ActionAxis::Thrust,
+                AxisBinding {
+                    positive: vec![Key(KeyCode::W)],
+                    negative: vec![Key(KeyCode::S)],
+                    gamepad_axes: vec![GamepadAxisType::LeftStickY],
+                },
+            ),
+            (
+                ActionAxis::Turn,
+                AxisBinding {
+                    positive: vec![Key(KeyCode::D)],
+                    negative: vec![Key(KeyCode::A)],
+                    gamepad_axes: vec![GamepadAxisType::LeftStickX],
+                },
+            ),
+            (
+                ActionAxis::Roll,
+                AxisBinding {
+                    positive: vec![Key(KeyCode::E)],
+                    negative: vec![Key(KeyCode::Q)],
+                    gamepad_axes: vec![GamepadAxisType::RightStickX],
+                },
+            ),
+        ]);
+
+        Self {
+            dead_zone: DEFAULT_DEAD_ZONE,
+            actions,
+            axes,
+        }
+    }
+}
+
+/// What the player is asking for this frame, resolved from `Bindings`.
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    axes: HashMap<ActionAxis, f32>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    /// Only reliable outside `FixedUpdate`, which may skip the frame the
+    /// action was pressed in.
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    pub fn axis(&self, axis: ActionAxis) -> f32 {
+        self.axes.get(&axis).copied().unwrap_or(0.0)
+    }
+}
+
+#[cfg(test)]
+impl ActionState {
+    /// Holds `action` down as if it was pressed this frame.
+    pub fn press(&mut self, action: Action) {
+        self.pressed.insert(action);
+        self.just_pressed.insert(action);
+    }
+
+    pub fn set_axis(&mut self, axis: ActionAxis, value: f32) {
+        self.axes.insert(axis, value);
+    }
+}
+
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_bindings())
+            .init_resource::<ActionState>()
+            .add_systems(PreUpdate, update_action_state.after(InputSystem));
+    }
+}
+
+fn load_bindings() -> Bindings {
+    let Ok(contents) = fs::read_to_string(BINDINGS_PATH) else {
+        let bindings = Bindings::default();
+        // relative to the working directory, so say where it ended up
+        let path = std::env::current_dir()
+            .map_or_else(|_| BINDINGS_PATH.into(), |dir| dir.join(BINDINGS_PATH));
+        let written = ron::ser::to_string_pretty(&bindings, PrettyConfig::default())
+            .map_err(|error| error.to_string())
+            .and_then(|contents| fs::write(&path, contents).map_err(|error| error.to_string()));
+        match written {
+            Ok(()) => info!("Wrote the default bindings to {}", path.display()),
+            Err(error) => warn!(
+                "Failed to write the default bindings to {}: {error}",
+                path.display()
+            ),
+        }
+        return bindings;
+    };
+
+    match ron::from_str::<Bindings>(&contents) {
+        Ok(mut bindings) => {
+            // actions and axes added since the file was written keep their
+            // defaults
+            let defaults = Bindings::default();
+            for (action, action_bindings) in defaults.actions {
+                bindings.actions.entry(action).or_insert(action_bindings);
+            }
+            for (axis, axis_binding) in defaults.axes {
+                bindings.axes.entry(axis).or_insert(axis_binding);
+            }
+            bindings
+        }
+        Err(error) => {
+            warn!("Malformed bindings in {BINDINGS_PATH}, using the defaults: {error}");
+            Bindings::default()
+        }
+    }
+}
+
+/// Resolves raw keyboard and gamepad input into the `ActionState`. Anything
+/// that overrides the state, such as replays, runs after this.
+pub fn update_action_state(
+    mut action_state: ResMut<ActionState>,
+    bindings: Res<Bindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+) {
+    let (gamepads, gamepad_axes) = (&*gamepads, &*gamepad_axes);
+    let pressed = |binding: &InputBinding| match *binding {
+        InputBinding::Key(key) => keyboard_input.pressed(key),
+        InputBinding::GamepadButton(button_type) => gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.pressed(GamepadButton::new(gamepad, button_type))),
+    };
+
+    let previously_pressed = std::mem::take(&mut action_state.pressed);
+    action_state.pressed = bindings
+        .actions
+        .iter()
+        .filter(|(_, action_bindings)| action_bindings.iter().any(pressed))
+        .map(|(&action, _)| action)
+        .collect();
+    action_state.just_pressed = action_state
+        .pressed
+        .difference(&previously_pressed)
+        .copied()
+        .collect();
+
+    action_state.axes = bindings
+        .axes
+        .iter()
+        .map(|(&axis, binding)| {
+            let digital: f32 = match (
+                binding.positive.iter().any(pressed),
+                binding.negative.iter().any(pressed),
+            ) {
+                (true, false) => 1.0,
+                (false, true) => -1.0,
+                _ => 0.0,
+            };
+            // the most deflected stick wins
+            let analog = binding
+                .gamepad_axes
+                .iter()
+                .flat_map(|&axis_type| {
+                    gamepads.iter().filter_map(move |gamepad| {
+                        gamepad_axes.get(GamepadAxis::new(gamepad, axis_type))
+                    })
+                })
+                .filter(|value| value.abs() > bindings.dead_zone)
+                .fold(0.0_f32, |best, value| {
+                    if value.abs() > best.abs() {
+                        value
+                    } else {
+                        best
+                    }
+                });
+            let value = if analog.abs() > digital.abs() {
+                analog
+            } else {
+                digital
+            };
+            (axis, value.clamp(-1.0, 1.0))
+        })
+        .collect();
+}
@@ -1,97 +1,210 @@
-// std
-use std::{f32::consts::PI, ops::Range};
-
 // third party
 use bevy::prelude::*;
 use rand::Rng;
 
 // project internal
 use super::{
-    collision_detection::{Collider, CollisionDemage},
-    movement::{Acceleration, Velocity},
+    collision_detection::{Collider, CollisionDemage, CollisionLayer},
+    demage::DemageType,
+    interpolation::TransformInterpolation,
+    movement::{Acceleration, Mass, Velocity},
+    physics::Bounces,
+    playfield::Wraps,
     rotation::RotationVelocity,
     schedule::InGameSet,
+    score::ScoreValue,
+    waves::run_wave_director,
 };
 use crate::{
-    bundles::moving_object::MovingObjectBundle, health::Health,
-    resources::asset_loader::SceneAssets,
+    bundles::moving_object::MovingObjectBundle,
+    health::Health,
+    resources::{
+        asset_loader::SceneAssets,
+        game_config::{AsteroidConfig, GameConfig},
+        rng::GameRng,
+    },
+    state::{GameState, StateScoped},
 };
 
-const VELOCITY_SCALAR: f32 = 5.0;
-const ACCELERATION_SCALAR: f32 = 1.0;
-const SPAWN_RANGE_X: Range<f32> = -25.0..25.0;
-const SPAWN_RANGE_Z: Range<f32> = 0.0..25.0;
-const ROTATION_RANGE: Range<f32> = -PI..PI;
-const SPAWN_TIME_SECONDS: f32 = 2.0;
-const COLLIDER_RADIUS: f32 = 2.0;
-const HEALTH: f32 = 5.0;
-const COLLISION_DEMAGE: f32 = 35.0;
-
 #[derive(Component, Debug)]
 pub struct Asteroid;
 
-#[derive(Resource, Debug)]
-pub struct SpawnTimer {
-    timer: Timer,
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsteroidSize {
+    Large,
+    Medium,
+    Small,
+}
+
+impl AsteroidSize {
+    /// The size of the pieces this asteroid breaks into, if any.
+    pub fn fragment(self) -> Option<Self> {
+        match self {
+            AsteroidSize::Large => Some(AsteroidSize::Medium),
+            AsteroidSize::Medium => Some(AsteroidSize::Small),
+            AsteroidSize::Small => None,
+        }
+    }
+}
+
+/// Asks for one large asteroid at a random spot in the spawn area.
+#[derive(Event, Debug)]
+pub struct SpawnAsteroidEvent {
+    pub velocity_scalar: f32,
+    pub acceleration_scalar: f32,
+}
+
+impl SpawnAsteroidEvent {
+    pub fn new(velocity_scalar: f32, acceleration_scalar: f32) -> Self {
+        Self {
+            velocity_scalar,
+            acceleration_scalar,
+        }
+    }
 }
 
 pub struct AsteroidPlugin;
 
 impl Plugin for AsteroidPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(SpawnTimer {
-            timer: Timer::from_seconds(SPAWN_TIME_SECONDS, TimerMode::Repeating),
-        })
-        .add_systems(Update, spawn_asteroid.in_set(InGameSet::EntityUpdates));
+        app.add_event::<SpawnAsteroidEvent>()
+            .add_systems(
+                FixedUpdate,
+                spawn_asteroid
+                    .after(run_wave_director)
+                    .in_set(InGameSet::EntityUpdates),
+            )
+            .add_systems(
+                FixedUpdate,
+                split_dead_asteroids.in_set(InGameSet::EntityDeaths),
+            );
     }
 }
 
-fn spawn_asteroid(
+/// Spawns the asteroids asked for this step, rolling `GameRng` for where and
+/// how they move.
+pub fn spawn_asteroid(
     mut commands: Commands,
-    mut spawn_timer: ResMut<SpawnTimer>,
-    time: Res<Time>,
+    mut spawn_asteroid_event_reader: EventReader<SpawnAsteroidEvent>,
     scene_assets: Res<SceneAssets>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
 ) {
-    spawn_timer.timer.tick(time.delta());
-    if !spawn_timer.timer.just_finished() {
-        return;
-    }
+    let config = &config.asteroids;
 
-    let mut rng = rand::thread_rng();
+    for &SpawnAsteroidEvent {
+        velocity_scalar,
+        acceleration_scalar,
+    } in spawn_asteroid_event_reader.read()
+    {
+        let translation = Vec3::new(
+            rng.gen_range(config.spawn_range_x.clone()),
+            0.0,
+            rng.gen_range(config.spawn_range_z.clone()),
+        );
 
-    let translation = Vec3::new(
-        rng.gen_range(SPAWN_RANGE_X),
-        0.0,
-        rng.gen_range(SPAWN_RANGE_Z),
-    );
+        let velocity = random_unit_vector(&mut *rng) * velocity_scalar;
+        let acceleration = random_unit_vector(&mut *rng) * acceleration_scalar;
 
-    let rotation = Vec3::new(
-        rng.gen_range(ROTATION_RANGE),
-        rng.gen_range(ROTATION_RANGE),
-        rng.gen_range(ROTATION_RANGE),
-    );
+        commands.spawn(asteroid_bundle(
+            AsteroidSize::Large,
+            translation,
+            velocity,
+            acceleration,
+            random_rotation(&mut *rng, config),
+            config,
+            &scene_assets,
+        ));
+    }
+}
 
-    let mut random_unit_vector =
-        || Vec3::new(rng.gen_range(-1.0..1.0), 0., rng.gen_range(-1.0..1.0)).normalize_or_zero();
+/// Replaces each dead asteroid with smaller ones, rolling `GameRng` for
+/// their headings.
+pub fn split_dead_asteroids(
+    mut commands: Commands,
+    query: Query<(&Transform, &Velocity, &Acceleration, &Health, &AsteroidSize), With<Asteroid>>,
+    scene_assets: Res<SceneAssets>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+) {
+    let config = &config.asteroids;
 
-    let velocity = random_unit_vector() * VELOCITY_SCALAR;
-    let acceleration = random_unit_vector() * ACCELERATION_SCALAR;
+    for (transform, velocity, acceleration, health, size) in query.iter() {
+        if !health.is_dead() {
+            continue;
+        }
+        let Some(fragment_size) = size.fragment() else {
+            continue;
+        };
 
-    commands.spawn((
+        let fragment_radius = config.collider_radius * config.size(fragment_size).scale;
+        for _ in 0..rng.gen_range(config.fragment_count.clone()) {
+            let direction = random_unit_vector(&mut *rng);
+            commands.spawn(asteroid_bundle(
+                fragment_size,
+                transform.translation + direction * fragment_radius,
+                velocity.value + direction * config.fragment_velocity_scalar,
+                acceleration.value,
+                random_rotation(&mut *rng, config),
+                config,
+                &scene_assets,
+            ));
+        }
+    }
+}
+
+fn asteroid_bundle(
+    size: AsteroidSize,
+    translation: Vec3,
+    velocity: Vec3,
+    acceleration: Vec3,
+    rotation: Vec3,
+    config: &AsteroidConfig,
+    scene_assets: &SceneAssets,
+) -> impl Bundle {
+    let size_config = config.size(size);
+    let scale = size_config.scale;
+    (
         MovingObjectBundle {
+            interpolation: TransformInterpolation::default(),
             velocity: Velocity::new(velocity),
             acceleration: Acceleration::new(acceleration),
             rotation_velocity: RotationVelocity::new(rotation),
             model: SceneBundle {
                 scene: scene_assets.asteroid.clone(),
-                transform: Transform::from_translation(translation),
+                transform: Transform::from_translation(translation).with_scale(Vec3::splat(scale)),
                 ..default()
             },
-            collider: Collider::new(COLLIDER_RADIUS),
+            collider: Collider::new(
+                config.collider_radius * scale,
+                CollisionLayer::ENEMY,
+                CollisionLayer::PLAYER | CollisionLayer::PLAYER_PROJECTILE,
+            ),
         },
         Name::new("Asteroid"),
+        StateScoped(GameState::InGame),
         Asteroid,
-        Health::new(HEALTH),
-        CollisionDemage::new(COLLISION_DEMAGE),
-    ));
+        Wraps,
+        Bounces,
+        size,
+        ScoreValue::new(size_config.points),
+        Health::new(config.health * scale),
+        CollisionDemage::new(config.collision_demage * scale, DemageType::Kinetic),
+        // mass grows with volume
+        Mass::new(config.mass * scale.powi(3)),
+        config.resistances,
+    )
+}
+
+fn random_unit_vector(rng: &mut impl Rng) -> Vec3 {
+    Vec3::new(rng.gen_range(-1.0..1.0), 0., rng.gen_range(-1.0..1.0)).normalize_or_zero()
+}
+
+fn random_rotation(rng: &mut impl Rng, config: &AsteroidConfig) -> Vec3 {
+    let range = -config.max_rotation_speed..config.max_rotation_speed;
+    Vec3::new(
+        rng.gen_range(range.clone()),
+        rng.gen_range(range.clone()),
+        rng.gen_range(range),
+    )
 }
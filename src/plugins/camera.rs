@@ -1,18 +1,39 @@
+use std::f32::consts::FRAC_PI_4;
+
 use bevy::prelude::*;
 
-const CAMERA_DISTANCE: f32 = 80.0;
+use crate::resources::game_config::GameConfig;
+
+/// Vertical field of view in radians.
+pub const FOV: f32 = FRAC_PI_4;
+/// The window shape `playfield.half_extents` is checked against.
+pub const ASPECT_RATIO: f32 = 16.0 / 9.0;
 
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_camera);
+        app.add_systems(Startup, spawn_camera).add_systems(
+            Update,
+            update_camera_distance.run_if(resource_exists_and_changed::<GameConfig>()),
+        );
     }
 }
 
 fn spawn_camera(mut commands: Commands) {
     commands.spawn(Camera3dBundle {
-        transform: Transform::from_xyz(0.0, CAMERA_DISTANCE, 0.0).looking_at(Vec3::ZERO, Vec3::Z),
+        projection: PerspectiveProjection {
+            fov: FOV,
+            ..default()
+        }
+        .into(),
         ..default()
     });
 }
+
+fn update_camera_distance(mut query: Query<&mut Transform, With<Camera>>, config: Res<GameConfig>) {
+    for mut transform in query.iter_mut() {
+        *transform =
+            Transform::from_xyz(0.0, config.camera.distance, 0.0).looking_at(Vec3::ZERO, Vec3::Z);
+    }
+}
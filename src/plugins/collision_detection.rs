@@ -1,39 +1,74 @@
-use bevy::{
-    prelude::*,
-    utils::hashbrown::{HashMap, HashSet},
-};
+pub mod broad_phase;
 
-use crate::health::Health;
+use std::ops::BitOr;
 
-use super::{
-    asteroids::Asteroid,
-    schedule::InGameSet,
-    spaceship::{Spaceship, SpaceshipMissile},
-};
+use bevy::prelude::*;
+
+use crate::resources::game_config::GameConfig;
+
+use broad_phase::Body;
+
+use super::{demage::DemageType, playfield::Wraps, schedule::InGameSet};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CollisionLayer(u32);
+
+impl CollisionLayer {
+    pub const PLAYER: Self = Self(1 << 0);
+    pub const PLAYER_PROJECTILE: Self = Self(1 << 1);
+    pub const ENEMY: Self = Self(1 << 2);
+    pub const ENEMY_PROJECTILE: Self = Self(1 << 3);
+    pub const PICKUP: Self = Self(1 << 4);
+    pub const ALL: Self = Self(u32::MAX);
+
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for CollisionLayer {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
 
 #[derive(Component, Debug, Default)]
 pub struct Collider {
     pub radius: f32,
+    /// The layer this collider lives on.
+    pub layer: CollisionLayer,
+    /// The layers this collider wants to collide with. A pair only collides
+    /// when each side's mask contains the other side's layer.
+    pub mask: CollisionLayer,
     pub colliding_entities: Vec<Entity>,
 }
 
 impl Collider {
-    pub fn new(radius: f32) -> Self {
+    pub fn new(radius: f32, layer: CollisionLayer, mask: CollisionLayer) -> Self {
         Self {
             radius,
+            layer,
+            mask,
             colliding_entities: vec![],
         }
     }
 }
 
+/// Demage dealt to whatever this entity collides with.
 #[derive(Component, Debug)]
 pub struct CollisionDemage {
     pub amount: f32,
+    pub demage_type: DemageType,
 }
 
 impl CollisionDemage {
-    pub fn new(amount: f32) -> Self {
-        Self { amount }
+    pub fn new(amount: f32, demage_type: DemageType) -> Self {
+        Self {
+            amount,
+            demage_type,
+        }
     }
 }
 
@@ -57,112 +92,70 @@ pub struct CollisionDetectionPlugin;
 impl Plugin for CollisionDetectionPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update,
+            FixedUpdate,
             collision_detection.in_set(InGameSet::CollisionDetection),
         )
         .add_systems(
-            Update,
-            (
-                (
-                    handle_collisions::<Asteroid>,
-                    handle_collisions::<Spaceship>,
-                    handle_collisions::<SpaceshipMissile>,
-                ),
-                apply_collision_demage,
-            )
-                .chain()
-                .in_set(InGameSet::EntityUpdates),
+            FixedUpdate,
+            handle_collisions.in_set(InGameSet::EntityUpdates),
         )
         .add_event::<CollisionEvent>();
     }
 }
 
-fn collision_detection(mut query: Query<(Entity, &mut Collider, &GlobalTransform)>) {
-    let mut colliding_entities: HashMap<Entity, Vec<Entity>> = HashMap::new();
-
-    // detection argo 1
-    // for (entity, collider, global_transform) in &query {
-    //     for (other, other_collider, other_global_transform) in
-    //         query.iter().filter(|&(e, ..)| e != entity)
-    //     {
-    //         let distance = global_transform
-    //             .translation()
-    //             .distance(other_global_transform.translation());
-    //         if distance < collider.radius + other_collider.radius {
-    //             colliding_entities
-    //                 .entry(entity)
-    //                 .or_insert_with(Vec::new)
-    //                 .push(other);
-    //         }
-    //     }
-    // } // end of detection argo 1
-
-    // detection argo 2
-    let mut checked_entities: HashSet<Entity> = HashSet::new();
-    for (entity, collider, global_transform) in &query {
-        for (other, other_collider, other_global_transform) in
-            query.iter().filter(|&(e, ..)| e != entity)
-        {
-            if checked_entities.contains(&other) {
-                continue;
-            }
-            let distance = global_transform
-                .translation()
-                .distance(other_global_transform.translation());
-            if distance < collider.radius + other_collider.radius {
-                colliding_entities
-                    .entry(entity)
-                    .or_insert_with(Vec::new)
-                    .push(other);
-                colliding_entities
-                    .entry(other)
-                    .or_insert_with(Vec::new)
-                    .push(entity);
-            }
-        }
-        checked_entities.insert(entity);
-    } // end of detection argo 2
+/// Fills in every `Collider::colliding_entities` from this step's positions.
+/// Anything that moves colliders apart again, like bouncing, runs after this.
+pub fn collision_detection(
+    mut query: Query<(Entity, &mut Collider, &Transform, Has<Wraps>)>,
+    config: Res<GameConfig>,
+) {
+    let (entities, bodies): (Vec<Entity>, Vec<Body>) = query
+        .iter()
+        .map(|(entity, collider, transform, _)| {
+            (
+                entity,
+                Body::new(
+                    transform.translation,
+                    collider.radius,
+                    collider.layer,
+                    collider.mask,
+                ),
+            )
+        })
+        .unzip();
+
+    let pairs = if config.playfield.wrap {
+        let wraps: Vec<bool> = query.iter().map(|(_, _, _, wraps)| wraps).collect();
+        broad_phase::wrapped_overlapping_pairs(&bodies, &wraps, config.playfield.half_extents)
+    } else {
+        broad_phase::overlapping_pairs(&bodies)
+    };
+
+    let mut colliding_entities: Vec<Vec<usize>> = vec![Vec::new(); bodies.len()];
+    for (i, j) in pairs {
+        colliding_entities[i].push(j);
+        colliding_entities[j].push(i);
+    }
 
-    for (entity, mut collider, _) in &mut query {
+    for ((_, mut collider, _, _), mut collisions) in query.iter_mut().zip(colliding_entities) {
+        // keep the query order so results don't depend on the sweep order
+        collisions.sort_unstable();
         collider.colliding_entities.clear();
-        if let Some(collisions) = colliding_entities.get(&entity) {
-            collider
-                .colliding_entities
-                .extend(collisions.iter().copied())
-        }
+        collider
+            .colliding_entities
+            .extend(collisions.into_iter().map(|i| entities[i]));
     }
 }
 
-fn handle_collisions<T: Component>(
+/// Sends a `CollisionEvent` for each side of every overlapping pair. Anything
+/// reacting to collisions, like picking up pickups, runs after this.
+pub fn handle_collisions(
     mut collision_event_writer: EventWriter<CollisionEvent>,
-    query: Query<(Entity, &Collider), With<T>>,
+    query: Query<(Entity, &Collider)>,
 ) {
     for (entity, collider) in query.iter() {
         for &collided_entity in collider.colliding_entities.iter() {
-            if query.get(collided_entity).is_ok() {
-                continue;
-            }
             collision_event_writer.send(CollisionEvent::new(entity, collided_entity));
         }
     }
 }
-
-fn apply_collision_demage(
-    mut collision_event_reader: EventReader<CollisionEvent>,
-    mut health_query: Query<&mut Health>,
-    collision_demage_query: Query<&CollisionDemage>,
-) {
-    for &CollisionEvent {
-        entity,
-        collided_entity,
-    } in collision_event_reader.read()
-    {
-        let Ok(mut health) = health_query.get_mut(entity) else {
-            continue;
-        };
-        let Ok(collision_demage) = collision_demage_query.get(collided_entity) else {
-            continue;
-        };
-        health.value -= collision_demage.amount;
-    }
-}
@@ -0,0 +1,262 @@
+use bevy::prelude::*;
+
+use super::CollisionLayer;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Body {
+    pub position: Vec3,
+    pub radius: f32,
+    pub layer: CollisionLayer,
+    pub mask: CollisionLayer,
+}
+
+impl Body {
+    pub fn new(position: Vec3, radius: f32, layer: CollisionLayer, mask: CollisionLayer) -> Self {
+        Self {
+            position,
+            radius,
+            layer,
+            mask,
+        }
+    }
+
+    fn min_x(&self) -> f32 {
+        self.position.x - self.radius
+    }
+
+    fn max_x(&self) -> f32 {
+        self.position.x + self.radius
+    }
+
+    fn interacts_with(&self, other: &Body) -> bool {
+        self.mask.intersects(other.layer) && other.mask.intersects(self.layer)
+    }
+
+    fn overlaps(&self, other: &Body) -> bool {
+        self.position.distance(other.position) < self.radius + other.radius
+    }
+}
+
+/// Sweep and prune along the x axis: bodies are sorted by the left edge of
+/// their bounds, so each one only needs testing against the bodies whose
+/// bounds start before its own bounds end.
+///
+/// Returns every overlapping pair whose layers interact as indices into `bodies`, smaller index
+/// first.
+pub fn overlapping_pairs(bodies: &[Body]) -> Vec<(usize, usize)> {
+    let mut order: Vec<usize> = (0..bodies.len()).collect();
+    order.sort_unstable_by(|&a, &b| bodies[a].min_x().total_cmp(&bodies[b].min_x()));
+
+    let mut pairs = Vec::new();
+    for (n, &i) in order.iter().enumerate() {
+        let max_x = bodies[i].max_x();
+        for &j in &order[n + 1..] {
+            if bodies[j].min_x() > max_x {
+                break;
+            }
+            if bodies[i].interacts_with(&bodies[j]) && bodies[i].overlaps(&bodies[j]) {
+                pairs.push((i.min(j), i.max(j)));
+            }
+        }
+    }
+    pairs
+}
+
+/// [`overlapping_pairs`] on a playfield that wraps around: bodies flagged in
+/// `wraps` that reach over an edge are also tested as a copy placed at the
+/// opposite edge.
+pub fn wrapped_overlapping_pairs(
+    bodies: &[Body],
+    wraps: &[bool],
+    half_extents: Vec2,
+) -> Vec<(usize, usize)> {
+    let mut extended = bodies.to_vec();
+    let mut originals: Vec<usize> = (0..bodies.len()).collect();
+    for (i, body) in bodies.iter().enumerate().filter(|&(i, _)| wraps[i]) {
+        let x_shifts = edge_shifts(body.position.x, body.radius, half_extents.x);
+        let z_shifts = edge_shifts(body.position.z, body.radius, half_extents.y);
+        for &x_shift in &x_shifts {
+            for &z_shift in &z_shifts {
+                if x_shift == 0.0 && z_shift == 0.0 {
+                    continue;
+                }
+                extended.push(Body {
+                    position: body.position + Vec3::new(x_shift, 0.0, z_shift),
+                    ..*body
+                });
+                originals.push(i);
+            }
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = overlapping_pairs(&extended)
+        .into_iter()
+        .map(|(i, j)| (originals[i], originals[j]))
+        .filter(|(i, j)| i != j)
+        .map(|(i, j)| (i.min(j), i.max(j)))
+        .collect();
+    pairs.sort_unstable();
+    pairs.dedup();
+    pairs
+}
+
+/// Offsets at which a body spanning `value ± radius` shows up on a wrapping
+/// axis, including its own position.
+fn edge_shifts(value: f32, radius: f32, half_extent: f32) -> Vec<f32> {
+    let mut shifts = vec![0.0];
+    if value + radius > half_extent {
+        shifts.push(-2.0 * half_extent);
+    }
+    if value - radius < -half_extent {
+        shifts.push(2.0 * half_extent);
+    }
+    shifts
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        plugins::{
+            collision_detection::{Collider, CollisionDetectionPlugin},
+            playfield::Wraps,
+        },
+        resources::game_config::GameConfig,
+        state::GameState,
+        test_utils::{enter_state, headless_app},
+    };
+
+    const BODY_COUNT: usize = 5000;
+    const HALF_EXTENTS: Vec2 = Vec2::new(58.0, 33.0);
+    const LAYERS: [CollisionLayer; 5] = [
+        CollisionLayer::PLAYER,
+        CollisionLayer::PLAYER_PROJECTILE,
+        CollisionLayer::ENEMY,
+        CollisionLayer::ENEMY_PROJECTILE,
+        CollisionLayer::PICKUP,
+    ];
+
+    /// Random bodies with random layers and masks. Those that don't wrap stay
+    /// fully inside the playfield, like they would in the game.
+    fn random_bodies(rng: &mut impl Rng) -> (Vec<Body>, Vec<bool>) {
+        (0..BODY_COUNT)
+            .map(|_| {
+                let radius = rng.gen_range(0.3..1.5);
+                let wraps = rng.gen_bool(0.5);
+                let margin = if wraps { 0.0 } else { radius };
+                let position = Vec3::new(
+                    rng.gen_range(-HALF_EXTENTS.x + margin..HALF_EXTENTS.x - margin),
+                    0.0,
+                    rng.gen_range(-HALF_EXTENTS.y + margin..HALF_EXTENTS.y - margin),
+                );
+                let layer = LAYERS[rng.gen_range(0..LAYERS.len())];
+                let mask = LAYERS
+                    .into_iter()
+                    .filter(|_| rng.gen_bool(0.6))
+                    .fold(CollisionLayer::default(), |mask, layer| mask | layer);
+                (Body::new(position, radius, layer, mask), wraps)
+            })
+            .unzip()
+    }
+
+    /// Tests every pair against each other, moving a wrapping body by whole
+    /// playfield sizes to find it reaching in over an edge.
+    fn brute_force_pairs(bodies: &[Body], wraps: Option<&[bool]>) -> Vec<(usize, usize)> {
+        let size = 2.0 * HALF_EXTENTS;
+        let mut pairs = Vec::new();
+        for i in 0..bodies.len() {
+            for j in i + 1..bodies.len() {
+                if !bodies[i].interacts_with(&bodies[j]) {
+                    continue;
+                }
+                let wrapped = wraps.is_some_and(|wraps| wraps[i] || wraps[j]);
+                let shifts: &[f32] = if wrapped { &[-1.0, 0.0, 1.0] } else { &[0.0] };
+                let overlaps = shifts.iter().any(|&x| {
+                    shifts.iter().any(|&z| {
+                        let shift = Vec3::new(x * size.x, 0.0, z * size.y);
+                        (bodies[i].position + shift).distance(bodies[j].position)
+                            < bodies[i].radius + bodies[j].radius
+                    })
+                });
+                if overlaps {
+                    pairs.push((i, j));
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn sweep_matches_brute_force_and_is_faster() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let (bodies, wraps) = random_bodies(&mut rng);
+
+        let start = Instant::now();
+        let mut pairs = overlapping_pairs(&bodies);
+        let sweep_time = start.elapsed();
+        let start = Instant::now();
+        let expected = brute_force_pairs(&bodies, None);
+        let brute_force_time = start.elapsed();
+        pairs.sort_unstable();
+        assert!(!expected.is_empty());
+        assert_eq!(pairs, expected);
+
+        let start = Instant::now();
+        let wrapped_pairs = wrapped_overlapping_pairs(&bodies, &wraps, HALF_EXTENTS);
+        let wrapped_sweep_time = start.elapsed();
+        let wrapped_expected = brute_force_pairs(&bodies, Some(&wraps));
+        assert!(wrapped_expected.len() > expected.len());
+        assert_eq!(wrapped_pairs, wrapped_expected);
+
+        assert!(sweep_time < brute_force_time);
+        assert!(wrapped_sweep_time < brute_force_time);
+    }
+
+    #[test]
+    fn collision_detection_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let (bodies, wraps) = random_bodies(&mut rng);
+
+        for wrap in [false, true] {
+            let mut app = headless_app();
+            app.add_plugins(CollisionDetectionPlugin);
+            let mut config = app.world.resource_mut::<GameConfig>();
+            config.playfield.wrap = wrap;
+            config.playfield.half_extents = HALF_EXTENTS;
+            enter_state(&mut app, GameState::InGame);
+
+            let entities: Vec<Entity> = bodies
+                .iter()
+                .zip(&wraps)
+                .map(|(body, &body_wraps)| {
+                    let mut entity = app.world.spawn((
+                        Transform::from_translation(body.position),
+                        Collider::new(body.radius, body.layer, body.mask),
+                    ));
+                    if body_wraps {
+                        entity.insert(Wraps);
+                    }
+                    entity.id()
+                })
+                .collect();
+            app.update();
+
+            let mut expected = vec![Vec::new(); bodies.len()];
+            for (i, j) in brute_force_pairs(&bodies, wrap.then_some(&wraps)) {
+                expected[i].push(entities[j]);
+                expected[j].push(entities[i]);
+            }
+            for (&entity, mut expected) in entities.iter().zip(expected) {
+                let collider = app.world.get::<Collider>(entity).unwrap();
+                let mut colliding = collider.colliding_entities.clone();
+                colliding.sort_unstable();
+                expected.sort_unstable();
+                assert_eq!(colliding, expected);
+            }
+        }
+    }
+}
@@ -7,7 +7,7 @@ pub struct DebugPlugin;
 impl Plugin for DebugPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update,
+            FixedUpdate,
             (print_position, print_collisions)
                 .chain()
                 .after(InGameSet::EntityUpdates),
@@ -23,7 +23,7 @@ fn print_position(query: Query<(Entity, &Transform)>) {
 
 fn print_collisions(query: Query<(Entity, &Name, &Collider)>) {
     for (entity, name, collider) in &query {
-        if collider.colliding_entities.len() > 0 {
+        if !collider.colliding_entities.is_empty() {
             let collided_entities_name_list: Vec<_> = collider
                 .colliding_entities
                 .iter()
@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    collision_detection::{handle_collisions, Collider, CollisionDemage, CollisionEvent},
+    movement::{Mass, Velocity},
+    physics::Bounces,
+    schedule::InGameSet,
+    shield::Shield,
+    spaceship::Invulnerable,
+    weapon::Projectile,
+};
+use crate::{health::Health, resources::game_config::GameConfig};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum DemageType {
+    #[default]
+    Kinetic,
+    Explosive,
+    Energy,
+}
+
+/// Fraction of each type of demage that is shrugged off. Negative values
+/// make the entity weak to that type.
+#[derive(Component, Debug, Clone, Copy, Default, Deserialize)]
+pub struct Resistances {
+    #[serde(default)]
+    pub kinetic: f32,
+    #[serde(default)]
+    pub explosive: f32,
+    #[serde(default)]
+    pub energy: f32,
+}
+
+impl Resistances {
+    pub fn get(&self, demage_type: DemageType) -> f32 {
+        match demage_type {
+            DemageType::Kinetic => self.kinetic,
+            DemageType::Explosive => self.explosive,
+            DemageType::Energy => self.energy,
+        }
+    }
+}
+
+/// `source` hit `target`, touching it at `impact_point` on its surface.
+#[derive(Event, Debug)]
+pub struct DemageEvent {
+    pub target: Entity,
+    pub source: Entity,
+    pub amount: f32,
+    pub demage_type: DemageType,
+    pub impact_point: Vec3,
+}
+
+/// Pairs of (target, source) that recently dealt demage, so overlapping
+/// colliders hurt once per cooldown instead of every fixed step.
+#[derive(Resource, Debug, Default)]
+struct HitCooldowns {
+    timers: HashMap<(Entity, Entity), Timer>,
+}
+
+pub struct DemagePlugin;
+
+impl Plugin for DemagePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HitCooldowns>()
+            .add_event::<DemageEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    tick_hit_cooldowns,
+                    send_collision_demage_events,
+                    (apply_demage, apply_knockback),
+                )
+                    .chain()
+                    .after(handle_collisions)
+                    .in_set(InGameSet::EntityUpdates),
+            );
+    }
+}
+
+fn tick_hit_cooldowns(mut hit_cooldowns: ResMut<HitCooldowns>, time: Res<Time>) {
+    hit_cooldowns
+        .timers
+        .retain(|_, timer| !timer.tick(time.delta()).finished());
+}
+
+fn send_collision_demage_events(
+    mut collision_event_reader: EventReader<CollisionEvent>,
+    mut demage_event_writer: EventWriter<DemageEvent>,
+    mut hit_cooldowns: ResMut<HitCooldowns>,
+    query: Query<(&Transform, &Collider)>,
+    collision_demage_query: Query<&CollisionDemage>,
+    vulnerable_query: Query<(), (With<Health>, Without<Invulnerable>)>,
+    config: Res<GameConfig>,
+) {
+    for &CollisionEvent {
+        entity,
+        collided_entity,
+    } in collision_event_reader.read()
+    {
+        let Ok(collision_demage) = collision_demage_query.get(collided_entity) else {
+            continue;
+        };
+        // don't start a cooldown for a hit that can't hurt
+        if !vulnerable_query.contains(entity) {
+            continue;
+        }
+        let Ok([(transform, collider), (collided_transform, _)]) =
+            query.get_many([entity, collided_entity])
+        else {
+            continue;
+        };
+        if hit_cooldowns
+            .timers
+            .contains_key(&(entity, collided_entity))
+        {
+            continue;
+        }
+        hit_cooldowns.timers.insert(
+            (entity, collided_entity),
+            Timer::from_seconds(config.demage.hit_cooldown_seconds, TimerMode::Once),
+        );
+
+        let offset = config
+            .playfield
+            .offset(transform.translation, collided_transform.translation);
+        demage_event_writer.send(DemageEvent {
+            target: entity,
+            source: collided_entity,
+            amount: collision_demage.amount,
+            demage_type: collision_demage.demage_type,
+            impact_point: transform.translation + offset.normalize_or_zero() * collider.radius,
+        });
+    }
+}
+
+/// Takes demage off health, after resistances and whatever the shield soaks
+/// up.
+pub fn apply_demage(
+    mut demage_event_reader: EventReader<DemageEvent>,
+    mut health_query: Query<(&mut Health, Option<&mut Shield>), Without<Invulnerable>>,
+    resistances_query: Query<&Resistances>,
+    projectile_query: Query<&Projectile>,
+) {
+    for event in demage_event_reader.read() {
+        let Ok((mut health, shield)) = health_query.get_mut(event.target) else {
+            continue;
+        };
+        let resistance = resistances_query
+            .get(event.target)
+            .map_or(0.0, |resistances| resistances.get(event.demage_type));
+        let amount = event.amount * (1.0 - resistance);
+        let amount = match shield {
+            Some(mut shield) => shield.absorb(amount),
+            None => amount,
+        };
+        health.value -= amount;
+        health.last_attacker = Some(
+            projectile_query
+                .get(event.source)
+                .map_or(event.source, |projectile| projectile.owner),
+        );
+    }
+}
+
+/// Pushes the target away from the impact, harder the heavier and faster the
+/// source was compared to it. Bodies that bounce off each other already get
+/// pushed apart by the elastic collision.
+fn apply_knockback(
+    mut demage_event_reader: EventReader<DemageEvent>,
+    mut query: Query<(&Transform, &mut Velocity, &Mass, Has<Bounces>)>,
+    config: Res<GameConfig>,
+) {
+    for event in demage_event_reader.read() {
+        let Ok((_, source_velocity, source_mass, source_bounces)) = query.get(event.source) else {
+            continue;
+        };
+        let (source_velocity, source_mass) = (source_velocity.value, source_mass.value);
+        let Ok((transform, mut velocity, mass, bounces)) = query.get_mut(event.target) else {
+            continue;
+        };
+        if config.physics.elastic_collisions && bounces && source_bounces {
+            continue;
+        }
+
+        let total_mass = source_mass + mass.value;
+        let normal = (transform.translation - event.impact_point).normalize_or_zero();
+        let closing_speed = (source_velocity - velocity.value).dot(normal);
+        if total_mass <= 0.0 || closing_speed <= 0.0 {
+            continue;
+        }
+        velocity.value +=
+            normal * closing_speed * config.demage.knockback_scalar * source_mass / total_mass;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        plugins::collision_detection::{CollisionDetectionPlugin, CollisionLayer},
+        state::GameState,
+        test_utils::{enter_state, headless_app},
+    };
+
+    fn demage_app() -> App {
+        let mut app = headless_app();
+        app.add_plugins(CollisionDetectionPlugin)
+            .add_plugins(DemagePlugin);
+        enter_state(&mut app, GameState::InGame);
+        app
+    }
+
+    fn spawn_body(app: &mut App, x: f32, velocity: Vec3) -> Entity {
+        app.world
+            .spawn((
+                Transform::from_xyz(x, 0.0, 0.0),
+                Velocity::new(velocity),
+                Mass::new(1.0),
+                Health::new(100.0),
+            ))
+            .id()
+    }
+
+    /// Something that hurts whatever it collides with, never colliding by
+    /// itself so only the events sent by hand count.
+    fn spawn_source(app: &mut App, x: f32, velocity: Vec3) -> Entity {
+        let source = spawn_body(app, x, velocity);
+        app.world.entity_mut(source).insert((
+            Collider::new(1.0, CollisionLayer::ENEMY, CollisionLayer::default()),
+            CollisionDemage::new(10.0, DemageType::Kinetic),
+        ));
+        source
+    }
+
+    fn spawn_target(app: &mut App, x: f32) -> Entity {
+        let target = spawn_body(app, x, Vec3::ZERO);
+        app.world.entity_mut(target).insert(Collider::new(
+            1.0,
+            CollisionLayer::PLAYER,
+            CollisionLayer::default(),
+        ));
+        target
+    }
+
+    fn health(app: &App, entity: Entity) -> f32 {
+        app.world.get::<Health>(entity).unwrap().value
+    }
+
+    #[test]
+    fn resistances_scale_demage() {
+        let mut app = demage_app();
+        let target = spawn_target(&mut app, 0.0);
+        let source = spawn_source(&mut app, 5.0, Vec3::ZERO);
+        app.world.entity_mut(target).insert(Resistances {
+            kinetic: 0.5,
+            explosive: 0.0,
+            energy: -0.5,
+        });
+
+        let hit = |app: &mut App, demage_type| {
+            app.world.send_event(DemageEvent {
+                target,
+                source,
+                amount: 20.0,
+                demage_type,
+                impact_point: Vec3::ZERO,
+            });
+            app.update();
+        };
+        hit(&mut app, DemageType::Kinetic);
+        assert_eq!(health(&app, target), 90.0);
+        hit(&mut app, DemageType::Explosive);
+        assert_eq!(health(&app, target), 70.0);
+        // a negative resistance is a weakness
+        hit(&mut app, DemageType::Energy);
+        assert_eq!(health(&app, target), 40.0);
+    }
+
+    #[test]
+    fn hit_cooldown_stops_repeated_hits() {
+        let mut app = demage_app();
+        let target = spawn_target(&mut app, 0.0);
+        let source = spawn_source(&mut app, 1.0, Vec3::ZERO);
+
+        // 0.5 seconds of cooldown at 60 steps a second
+        for _ in 0..20 {
+            app.world.send_event(CollisionEvent::new(target, source));
+            app.update();
+        }
+        assert_eq!(health(&app, target), 90.0);
+
+        for _ in 0..20 {
+            app.update();
+        }
+        app.world.send_event(CollisionEvent::new(target, source));
+        app.update();
+        assert_eq!(health(&app, target), 80.0);
+    }
+
+    #[test]
+    fn knockback_pushes_across_a_wrapped_edge() {
+        let mut app = demage_app();
+        let half_width = app.world.resource::<GameConfig>().playfield.half_extents.x;
+        // just over the right edge from the target, flying left into it
+        let target = spawn_target(&mut app, half_width - 1.0);
+        let source = spawn_source(&mut app, -half_width + 1.0, Vec3::new(-10.0, 0.0, 0.0));
+
+        app.world.send_event(CollisionEvent::new(target, source));
+        app.update();
+
+        let velocity = app.world.get::<Velocity>(target).unwrap().value;
+        assert!(velocity.x < 0.0);
+        assert_eq!(velocity.z, 0.0);
+    }
+}
@@ -1,28 +1,79 @@
 use bevy::prelude::*;
 
-use crate::{health::Health, state::GameState};
+use crate::{health::Health, resources::game_config::GameConfig};
 
-use super::schedule::InGameSet;
+use super::{
+    movement::{update_position, Velocity},
+    playfield::Wraps,
+    schedule::InGameSet,
+};
 
-const DESPAWN_DISTANCE: f32 = 100.0;
+/// Despawns a short-lived entity once it has existed for a while, travelled
+/// far enough, or whichever comes first when both are set.
+#[derive(Component, Debug)]
+pub struct Lifetime {
+    timer: Option<Timer>,
+    max_distance: Option<f32>,
+    travelled: f32,
+}
+
+impl Lifetime {
+    pub fn new(seconds: Option<f32>, max_distance: Option<f32>) -> Self {
+        Self {
+            timer: seconds.map(|seconds| Timer::from_seconds(seconds, TimerMode::Once)),
+            max_distance,
+            travelled: 0.0,
+        }
+    }
+
+    pub fn from_seconds(seconds: f32) -> Self {
+        Self::new(Some(seconds), None)
+    }
+
+    fn is_expired(&self) -> bool {
+        let timed_out = self.timer.as_ref().is_some_and(Timer::finished);
+        let out_of_range = self
+            .max_distance
+            .is_some_and(|max_distance| self.travelled >= max_distance);
+        timed_out || out_of_range
+    }
+}
 
 pub struct DespawnPlugin;
 
 impl Plugin for DespawnPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update,
-            (despawn_far_away_entities, despawn_dead_entities).in_set(InGameSet::DespawnEntities),
+            FixedUpdate,
+            (
+                despawn_far_away_entities,
+                despawn_dead_entities,
+                despawn_expired_entities,
+            )
+                .in_set(InGameSet::DespawnEntities),
         )
-        .add_systems(OnEnter(GameState::GameOver), despawn_all_entities);
+        .add_systems(
+            FixedUpdate,
+            age_lifetimes
+                .after(update_position)
+                .in_set(InGameSet::EntityUpdates),
+        );
     }
 }
 
-fn despawn_far_away_entities(mut commands: Commands, query: Query<(Entity, &GlobalTransform)>) {
-    for (entity, transform) in query.iter() {
-        let distance = transform.translation().distance(Vec3::ZERO);
+fn despawn_far_away_entities(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform, Has<Wraps>), With<Velocity>>,
+    config: Res<GameConfig>,
+) {
+    for (entity, transform, wraps) in query.iter() {
+        let far_away = if config.playfield.wrap {
+            !wraps && !config.playfield.contains(transform.translation)
+        } else {
+            transform.translation.distance(Vec3::ZERO) > config.despawn.distance
+        };
 
-        if distance > DESPAWN_DISTANCE {
+        if far_away {
             commands.entity(entity).despawn_recursive();
         }
     }
@@ -30,14 +81,65 @@ fn despawn_far_away_entities(mut commands: Commands, query: Query<(Entity, &Glob
 
 fn despawn_dead_entities(mut commands: Commands, query: Query<(Entity, &Health)>) {
     for (entity, health) in query.iter() {
-        if health.value <= 0.0 {
+        if health.is_dead() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Counts the step's movement as soon as it happened, so the range runs out
+/// on the step the entity crosses it.
+fn age_lifetimes(mut query: Query<(&mut Lifetime, Option<&Velocity>)>, time: Res<Time>) {
+    for (mut lifetime, velocity) in query.iter_mut() {
+        if let Some(timer) = &mut lifetime.timer {
+            timer.tick(time.delta());
+        }
+        // measured along the path rather than from the spawn point, so
+        // wrapping around the playfield still counts
+        if let Some(velocity) = velocity {
+            lifetime.travelled += velocity.value.length() * time.delta_seconds();
+        }
+    }
+}
+
+fn despawn_expired_entities(mut commands: Commands, query: Query<(Entity, &Lifetime)>) {
+    for (entity, lifetime) in query.iter() {
+        if lifetime.is_expired() {
             commands.entity(entity).despawn_recursive();
         }
     }
 }
 
-fn despawn_all_entities(mut commands: Commands, query: Query<Entity, With<Health>>) {
-    for entity in query.iter() {
-        commands.entity(entity).despawn_recursive();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::GameState,
+        test_utils::{enter_state, headless_app},
+    };
+
+    #[test]
+    fn projectiles_leaving_the_playfield_are_despawned() {
+        let mut app = headless_app();
+        app.add_plugins(DespawnPlugin);
+        enter_state(&mut app, GameState::InGame);
+
+        let outside = Transform::from_xyz(100.0, 0.0, 0.0);
+        let projectile = app
+            .world
+            .spawn((
+                outside,
+                Velocity::new(Vec3::X),
+                Lifetime::from_seconds(10.0),
+            ))
+            .id();
+        let asteroid = app
+            .world
+            .spawn((outside, Velocity::new(Vec3::X), Wraps))
+            .id();
+        app.update();
+
+        assert!(app.world.get_entity(projectile).is_none());
+        assert!(app.world.get_entity(asteroid).is_some());
     }
 }
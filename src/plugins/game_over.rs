@@ -0,0 +1,225 @@
+use bevy::{prelude::*, time::Stopwatch};
+
+use super::{
+    actions::{Action, ActionState},
+    schedule::InGameSet,
+    score::Score,
+};
+use crate::{
+    resources::game_config::GameConfig,
+    state::{GameState, NewRun, StateScoped},
+};
+
+const TITLE_FONT_SIZE: f32 = 64.0;
+const TEXT_FONT_SIZE: f32 = 32.0;
+
+/// How long the current run has been going, not counting pauses.
+#[derive(Resource, Debug, Default)]
+pub struct SurvivalTime {
+    pub stopwatch: Stopwatch,
+}
+
+#[derive(Resource, Debug, Default)]
+struct GameOverTimer {
+    timer: Timer,
+}
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SurvivalTime>()
+            .init_resource::<GameOverTimer>()
+            .add_systems(
+                FixedUpdate,
+                tick_survival_time.in_set(InGameSet::EntityUpdates),
+            )
+            .add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen)
+            .add_systems(
+                Update,
+                restart_after_game_over.run_if(in_state(GameState::GameOver)),
+            )
+            .add_systems(NewRun, reset_survival_time);
+    }
+}
+
+fn tick_survival_time(mut survival_time: ResMut<SurvivalTime>, time: Res<Time>) {
+    survival_time.stopwatch.tick(time.delta());
+}
+
+fn reset_survival_time(mut survival_time: ResMut<SurvivalTime>) {
+    survival_time.stopwatch.reset();
+}
+
+fn spawn_game_over_screen(
+    mut commands: Commands,
+    mut game_over_timer: ResMut<GameOverTimer>,
+    score: Res<Score>,
+    survival_time: Res<SurvivalTime>,
+    config: Res<GameConfig>,
+) {
+    game_over_timer.timer = Timer::from_seconds(config.game_over.timeout_seconds, TimerMode::Once);
+
+    let text_style = |font_size| TextStyle {
+        font_size,
+        color: Color::WHITE,
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                ..default()
+            },
+            Name::new("GameOverScreen"),
+            StateScoped(GameState::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "GAME OVER",
+                text_style(TITLE_FONT_SIZE),
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!("Score: {}", score.points),
+                text_style(TEXT_FONT_SIZE),
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!(
+                    "Time survived: {:.1}s",
+                    survival_time.stopwatch.elapsed_secs()
+                ),
+                text_style(TEXT_FONT_SIZE),
+            ));
+            parent.spawn(TextBundle::from_section(
+                "Press Enter or (A) to play again",
+                text_style(TEXT_FONT_SIZE),
+            ));
+        });
+}
+
+fn restart_after_game_over(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut game_over_timer: ResMut<GameOverTimer>,
+    action_state: Res<ActionState>,
+    time: Res<Time>,
+) {
+    game_over_timer.timer.tick(time.delta());
+    if action_state.just_pressed(Action::Confirm) || game_over_timer.timer.finished() {
+        next_state.set(GameState::InGame);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        health::Health,
+        plugins::{
+            despawn::DespawnPlugin,
+            spaceship::{Lives, Spaceship, SpaceshipPlugin},
+        },
+        resources::asset_loader::SceneAssets,
+        test_utils::{enter_state, headless_app},
+    };
+
+    /// Starts a run with something left over from it, then loses the last
+    /// life. Returns the leftover entity.
+    fn lose_last_life(app: &mut App) -> Entity {
+        app.add_plugins(SpaceshipPlugin)
+            .add_plugins(DespawnPlugin)
+            .add_plugins(GameOverPlugin)
+            .init_resource::<SceneAssets>()
+            .insert_resource(Score::new(0.0));
+        enter_state(app, GameState::MainMenu);
+        enter_state(app, GameState::InGame);
+
+        let leftover = app
+            .world
+            .spawn((Transform::default(), StateScoped(GameState::InGame)))
+            .id();
+        app.world.resource_mut::<Lives>().remaining = 1;
+        let mut spaceships = app.world.query_filtered::<&mut Health, With<Spaceship>>();
+        spaceships.single_mut(&mut app.world).value = 0.0;
+
+        for _ in 0..3 {
+            app.update();
+        }
+        assert_eq!(state(app), GameState::GameOver);
+        assert_eq!(spaceship_count(app), 0);
+        leftover
+    }
+
+    fn state(app: &App) -> GameState {
+        app.world.resource::<State<GameState>>().get().clone()
+    }
+
+    fn spaceship_count(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), With<Spaceship>>()
+            .iter(&app.world)
+            .count()
+    }
+
+    fn game_over_screen_count(app: &mut App) -> usize {
+        app.world
+            .query::<&StateScoped>()
+            .iter(&app.world)
+            .filter(|scope| scope.0 == GameState::GameOver)
+            .count()
+    }
+
+    /// The run stays on screen under the game over screen, and is cleared
+    /// out for a fresh one once it's dismissed.
+    fn assert_run_restarts(app: &mut App, leftover: Entity) {
+        assert!(app.world.get_entity(leftover).is_some());
+        assert_eq!(game_over_screen_count(app), 1);
+
+        for _ in 0..2 {
+            app.update();
+        }
+        assert_eq!(state(app), GameState::InGame);
+        assert!(app.world.get_entity(leftover).is_none());
+        assert_eq!(game_over_screen_count(app), 0);
+        assert_eq!(spaceship_count(app), 1);
+        let lives = app.world.resource::<GameConfig>().spaceship.lives;
+        assert_eq!(app.world.resource::<Lives>().remaining, lives);
+    }
+
+    #[test]
+    fn confirm_starts_a_new_run() {
+        let mut app = headless_app();
+        let leftover = lose_last_life(&mut app);
+
+        app.world
+            .resource_mut::<ActionState>()
+            .press(Action::Confirm);
+        assert_run_restarts(&mut app, leftover);
+    }
+
+    #[test]
+    fn timeout_starts_a_new_run() {
+        let mut app = headless_app();
+        app.world
+            .resource_mut::<GameConfig>()
+            .game_over
+            .timeout_seconds = 0.5;
+        let leftover = lose_last_life(&mut app);
+
+        // 0.5 seconds at 60 steps a second
+        for _ in 0..28 {
+            app.update();
+            assert_eq!(state(&app), GameState::GameOver);
+        }
+        assert_run_restarts(&mut app, leftover);
+    }
+}
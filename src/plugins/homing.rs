@@ -0,0 +1,122 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    collision_detection::Collider,
+    movement::{update_position, Velocity},
+    playfield::Wraps,
+    schedule::InGameSet,
+};
+use crate::resources::game_config::GameConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct HomingStats {
+    /// Half the angle in radians of the cone ahead of the projectile that
+    /// targets are picked from, up to pi for all around.
+    pub cone_angle: f32,
+    pub range: f32,
+    /// Radians per second.
+    pub turn_rate: f32,
+}
+
+/// Steers a projectile towards the nearest thing it can hit, picking a new
+/// target whenever the current one is gone.
+#[derive(Component, Debug)]
+pub struct Homing {
+    pub stats: HomingStats,
+    target: Option<Entity>,
+}
+
+impl Homing {
+    pub fn new(stats: HomingStats) -> Self {
+        Self {
+            stats,
+            target: None,
+        }
+    }
+}
+
+pub struct HomingPlugin;
+
+impl Plugin for HomingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            steer_homing_projectiles
+                .before(update_position)
+                .in_set(InGameSet::EntityUpdates),
+        );
+    }
+}
+
+fn steer_homing_projectiles(
+    mut query: Query<(
+        &mut Transform,
+        &mut Velocity,
+        &Collider,
+        Has<Wraps>,
+        &mut Homing,
+    )>,
+    target_query: Query<(Entity, &Transform, &Collider), Without<Homing>>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+) {
+    for (mut transform, mut velocity, collider, wraps, mut homing) in query.iter_mut() {
+        let speed = velocity.value.length();
+        if speed == 0.0 {
+            continue;
+        }
+        let heading = velocity.value / speed;
+        let translation = transform.translation;
+        // a projectile that doesn't wrap would fly off the edge chasing
+        // something on the other side
+        let offset_to = |target: Vec3| {
+            if wraps {
+                config.playfield.offset(translation, target)
+            } else {
+                target - translation
+            }
+        };
+
+        if !homing
+            .target
+            .is_some_and(|target| target_query.contains(target))
+        {
+            // the nearest thing in the cone that this projectile can hit
+            homing.target = target_query
+                .iter()
+                .filter(|(_, _, target_collider)| collider.mask.intersects(target_collider.layer))
+                .filter_map(|(entity, target_transform, _)| {
+                    let offset = offset_to(target_transform.translation);
+                    let distance = offset.length();
+                    (distance <= homing.stats.range
+                        && heading.angle_between(offset) <= homing.stats.cone_angle)
+                        .then_some((entity, distance))
+                })
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(entity, _)| entity);
+        }
+
+        let Some((_, target_transform, _)) = homing
+            .target
+            .and_then(|target| target_query.get(target).ok())
+        else {
+            continue;
+        };
+        let offset = offset_to(target_transform.translation);
+        let desired = (offset * Vec3::new(1.0, 0.0, 1.0)).normalize_or_zero();
+        if desired == Vec3::ZERO {
+            continue;
+        }
+
+        // turn towards the target, no faster than the turn rate
+        let max_turn = homing.stats.turn_rate * time.delta_seconds();
+        let turn = heading.angle_between(desired).min(max_turn);
+        let side = heading.cross(desired).y.signum();
+        let heading = Quat::from_rotation_y(side * turn) * heading;
+
+        velocity.value = heading * speed;
+        // the model's nose points along its local +Z
+        transform.look_to(-heading, Vec3::Y);
+    }
+}
@@ -0,0 +1,250 @@
+use bevy::prelude::*;
+
+use super::{
+    score::Score,
+    shield::Shield,
+    spaceship::{Lives, Spaceship},
+    waves::WaveDirector,
+};
+use crate::{health::Health, resources::game_config::GameConfig};
+
+const TEXT_FONT_SIZE: f32 = 24.0;
+const BAR_WIDTH: f32 = 200.0;
+const BAR_HEIGHT: f32 = 12.0;
+const HEALTH_COLOR: Color = Color::rgb(0.8, 0.2, 0.2);
+const SHIELD_COLOR: Color = Color::rgb(0.2, 0.6, 0.9);
+
+#[derive(Component, Debug)]
+struct Hud;
+
+#[derive(Component, Debug)]
+struct HealthBar;
+
+#[derive(Component, Debug)]
+struct ShieldBar;
+
+#[derive(Component, Debug)]
+struct ScoreText;
+
+#[derive(Component, Debug)]
+struct WaveText;
+
+#[derive(Component, Debug)]
+struct LivesText;
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_hud).add_systems(
+            Update,
+            (
+                toggle_hud,
+                update_health_bar,
+                update_shield_bar,
+                update_score_text.run_if(resource_exists_and_changed::<Score>()),
+                update_wave_text.run_if(resource_exists_and_changed::<WaveDirector>()),
+                update_lives_text.run_if(resource_exists_and_changed::<Lives>()),
+            ),
+        );
+    }
+}
+
+fn spawn_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(16.0),
+                    left: Val::Px(16.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+            Name::new("Hud"),
+            Hud,
+        ))
+        .with_children(|parent| {
+            spawn_bar(parent, "Health", HEALTH_COLOR, HealthBar);
+            spawn_bar(parent, "Shield", SHIELD_COLOR, ShieldBar);
+            parent.spawn((TextBundle::from_section("", text_style()), ScoreText));
+            parent.spawn((TextBundle::from_section("", text_style()), WaveText));
+            parent.spawn((TextBundle::from_section("", text_style()), LivesText));
+        });
+}
+
+fn spawn_bar(parent: &mut ChildBuilder, label: &str, color: Color, marker: impl Component) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.0),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(label, text_style()));
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(BAR_WIDTH),
+                        height: Val::Px(BAR_HEIGHT),
+                        ..default()
+                    },
+                    background_color: Color::rgba(1.0, 1.0, 1.0, 0.2).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: color.into(),
+                            ..default()
+                        },
+                        marker,
+                    ));
+                });
+        });
+}
+
+fn text_style() -> TextStyle {
+    TextStyle {
+        font_size: TEXT_FONT_SIZE,
+        color: Color::WHITE,
+        ..default()
+    }
+}
+
+/// Shows the HUD only while a spaceship is flying, checking again whenever
+/// one spawns or despawns.
+fn toggle_hud(
+    mut hud_query: Query<&mut Visibility, With<Hud>>,
+    added_query: Query<(), Added<Spaceship>>,
+    mut removed_spaceships: RemovedComponents<Spaceship>,
+    spaceship_query: Query<(), With<Spaceship>>,
+) {
+    if added_query.is_empty() && removed_spaceships.read().count() == 0 {
+        return;
+    }
+    let visibility = if spaceship_query.is_empty() {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    };
+    for mut hud_visibility in hud_query.iter_mut() {
+        *hud_visibility = visibility;
+    }
+}
+
+fn update_health_bar(
+    mut bar_query: Query<&mut Style, With<HealthBar>>,
+    health_query: Query<&Health, (With<Spaceship>, Changed<Health>)>,
+    config: Res<GameConfig>,
+) {
+    let Ok(health) = health_query.get_single() else {
+        return;
+    };
+    let fraction = health.value / config.spaceship.health;
+    for mut style in bar_query.iter_mut() {
+        style.width = Val::Percent(100.0 * fraction.clamp(0.0, 1.0));
+    }
+}
+
+fn update_shield_bar(
+    mut bar_query: Query<&mut Style, With<ShieldBar>>,
+    shield_query: Query<&Shield, (With<Spaceship>, Changed<Shield>)>,
+) {
+    let Ok(shield) = shield_query.get_single() else {
+        return;
+    };
+    let fraction = shield.energy / shield.capacity;
+    for mut style in bar_query.iter_mut() {
+        style.width = Val::Percent(100.0 * fraction.clamp(0.0, 1.0));
+    }
+}
+
+fn update_score_text(mut query: Query<&mut Text, With<ScoreText>>, score: Res<Score>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = if score.combo > 1 {
+            format!("Score: {}  x{}", score.points, score.combo)
+        } else {
+            format!("Score: {}", score.points)
+        };
+    }
+}
+
+fn update_wave_text(mut query: Query<&mut Text, With<WaveText>>, director: Res<WaveDirector>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("Wave: {}", director.wave);
+    }
+}
+
+fn update_lives_text(mut query: Query<&mut Text, With<LivesText>>, lives: Res<Lives>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("Lives: {}", lives.remaining);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        plugins::{
+            asteroids::SpawnAsteroidEvent, score::ScorePlugin, shield::ShieldPlugin,
+            spaceship::SpaceshipPlugin, ufo::SpawnUfoEvent, waves::WavesPlugin,
+        },
+        resources::asset_loader::SceneAssets,
+        state::GameState,
+        test_utils::{enter_state, headless_app},
+    };
+
+    /// HUD texts and bars touched since the last check.
+    #[derive(Resource, Debug, Default)]
+    struct Redraws(usize);
+
+    type RedrawFilter = Or<(Changed<Text>, Changed<Style>)>;
+
+    fn count_redraws(mut redraws: ResMut<Redraws>, query: Query<(), RedrawFilter>) {
+        redraws.0 += query.iter().count();
+    }
+
+    #[test]
+    fn idle_frames_leave_the_hud_alone() {
+        let mut app = headless_app();
+        app.world
+            .resource_mut::<GameConfig>()
+            .waves
+            .intermission_seconds = 100.0;
+        app.add_plugins((
+            HudPlugin,
+            ScorePlugin,
+            ShieldPlugin,
+            SpaceshipPlugin,
+            WavesPlugin,
+        ))
+        .add_event::<SpawnAsteroidEvent>()
+        .add_event::<SpawnUfoEvent>()
+        .init_resource::<SceneAssets>()
+        .init_resource::<Redraws>()
+        .add_systems(Last, count_redraws);
+        enter_state(&mut app, GameState::MainMenu);
+        enter_state(&mut app, GameState::InGame);
+        app.update();
+        assert!(app.world.resource::<Redraws>().0 > 0);
+
+        app.world.resource_mut::<Redraws>().0 = 0;
+        for _ in 0..120 {
+            app.update();
+        }
+        assert_eq!(app.world.resource::<Redraws>().0, 0);
+    }
+}
@@ -0,0 +1,148 @@
+use bevy::{prelude::*, transform::TransformSystem};
+
+use super::schedule::InGameSet;
+
+/// Smooths rendering of an entity simulated in `FixedUpdate`.
+///
+/// The simulation owns `Transform` during fixed steps. Between steps the
+/// rendered `Transform` is blended from the last two simulated states by how
+/// far the clock has run into the next step, and the simulated state is put
+/// back before the next step runs.
+#[derive(Component, Debug, Default)]
+pub struct TransformInterpolation {
+    previous: Option<Transform>,
+    current: Option<Transform>,
+}
+
+impl TransformInterpolation {
+    /// Moves both simulated states by `offset`, so a jump such as wrapping
+    /// around the playfield isn't smeared across the screen.
+    pub fn teleport(&mut self, offset: Vec3) {
+        for transform in [&mut self.previous, &mut self.current]
+            .into_iter()
+            .flatten()
+        {
+            transform.translation += offset;
+        }
+    }
+}
+
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            restore_simulated_transforms.before(InGameSet::EntityDeaths),
+        )
+        .add_systems(
+            FixedUpdate,
+            record_simulated_transforms.after(InGameSet::CollisionDetection),
+        )
+        .add_systems(
+            PostUpdate,
+            interpolate_transforms.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+fn restore_simulated_transforms(mut query: Query<(&mut Transform, &mut TransformInterpolation)>) {
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        if let Some(current) = interpolation.current {
+            *transform = current;
+        }
+        interpolation.previous = interpolation.current;
+    }
+}
+
+fn record_simulated_transforms(mut query: Query<(&Transform, &mut TransformInterpolation)>) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.current = Some(*transform);
+        if interpolation.previous.is_none() {
+            interpolation.previous = interpolation.current;
+        }
+    }
+}
+
+fn interpolate_transforms(
+    mut query: Query<(&mut Transform, &TransformInterpolation)>,
+    fixed_time: Res<Time<Fixed>>,
+) {
+    let alpha = fixed_time.overstep_percentage();
+    for (mut transform, interpolation) in query.iter_mut() {
+        let (Some(previous), Some(current)) = (interpolation.previous, interpolation.current)
+        else {
+            continue;
+        };
+        transform.translation = previous.translation.lerp(current.translation, alpha);
+        transform.rotation = previous.rotation.slerp(current.rotation, alpha);
+        transform.scale = previous.scale.lerp(current.scale, alpha);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::{
+        plugins::{
+            actions::{Action, ActionAxis, ActionState},
+            asteroids::Asteroid,
+            spaceship::Spaceship,
+        },
+        resources::rng::GameRng,
+        state::GameState,
+        test_utils::{add_gameplay_plugins, enter_state, headless_app},
+    };
+
+    /// Ten seconds of play.
+    const STEPS: u32 = 600;
+
+    /// Plays `STEPS` fixed steps while firing, turning and thrusting, drawing
+    /// a frame every `steps_per_frame` fixed steps, and returns the simulated
+    /// transforms of the spaceship and every asteroid.
+    fn play(steps_per_frame: f64) -> Vec<Transform> {
+        let mut app = headless_app();
+        add_gameplay_plugins(&mut app);
+        app.insert_resource(GameRng::from_seed(5));
+        enter_state(&mut app, GameState::MainMenu);
+        enter_state(&mut app, GameState::InGame);
+
+        let mut action_state = app.world.resource_mut::<ActionState>();
+        action_state.press(Action::Fire);
+        action_state.set_axis(ActionAxis::Turn, 0.3);
+        action_state.set_axis(ActionAxis::Thrust, 0.5);
+        let fixed_time = app.world.resource::<Time<Fixed>>();
+        let end = fixed_time.elapsed() + fixed_time.timestep() * STEPS;
+        // whole steps or less per frame, so no frame runs past `end`
+        let frame_time = fixed_time.timestep().mul_f64(steps_per_frame);
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(frame_time));
+        while app.world.resource::<Time<Fixed>>().elapsed() < end {
+            app.update();
+        }
+        assert_eq!(app.world.resource::<Time<Fixed>>().elapsed(), end);
+
+        let mut transforms: Vec<Transform> = app
+            .world
+            .query_filtered::<&TransformInterpolation, With<Spaceship>>()
+            .iter(&app.world)
+            .filter_map(|interpolation| interpolation.current)
+            .collect();
+        transforms.extend(
+            app.world
+                .query_filtered::<&TransformInterpolation, With<Asteroid>>()
+                .iter(&app.world)
+                .filter_map(|interpolation| interpolation.current),
+        );
+        transforms
+    }
+
+    #[test]
+    fn frame_rate_does_not_change_the_simulation() {
+        // 30 and 144 frames a second at 60 steps a second
+        let slow = play(2.0);
+        assert!(slow.len() > 1);
+        assert_eq!(slow, play(60.0 / 144.0));
+    }
+}
@@ -0,0 +1,250 @@
+use bevy::{app::AppExit, prelude::*};
+
+use super::actions::{Action, ActionState};
+use crate::{
+    resources::game_config::{FlightModel, GameConfig},
+    state::{restart_run, GameState, StateScoped},
+};
+
+const TITLE_FONT_SIZE: f32 = 64.0;
+const ITEM_FONT_SIZE: f32 = 32.0;
+const SELECTED_COLOR: Color = Color::rgb(1.0, 0.8, 0.2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuPage {
+    Main,
+    Pause,
+    Settings,
+}
+
+impl MenuPage {
+    fn title(self) -> &'static str {
+        match self {
+            MenuPage::Main => "MY BEVY GAME",
+            MenuPage::Pause => "PAUSED",
+            MenuPage::Settings => "SETTINGS",
+        }
+    }
+
+    fn items(self) -> &'static [MenuItem] {
+        match self {
+            MenuPage::Main => &[MenuItem::Play, MenuItem::Settings, MenuItem::Quit],
+            MenuPage::Pause => &[
+                MenuItem::Resume,
+                MenuItem::Restart,
+                MenuItem::Settings,
+                MenuItem::Quit,
+            ],
+            MenuPage::Settings => &[
+                MenuItem::FlightModel,
+                MenuItem::Wrap,
+                MenuItem::Bounce,
+                MenuItem::Back,
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MenuItem {
+    Play,
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+    FlightModel,
+    Wrap,
+    Bounce,
+    Back,
+}
+
+impl MenuItem {
+    fn label(self, state: &GameState, config: &GameConfig) -> String {
+        match self {
+            MenuItem::Play => "Play".to_string(),
+            MenuItem::Resume => "Resume".to_string(),
+            MenuItem::Restart => "Restart".to_string(),
+            MenuItem::Settings => "Settings".to_string(),
+            MenuItem::Quit if *state == GameState::Paused => "Quit to menu".to_string(),
+            MenuItem::Quit => "Quit".to_string(),
+            MenuItem::FlightModel => format!("Flight model: {:?}", config.spaceship.flight_model),
+            MenuItem::Wrap if config.playfield.wrap => "Screen wrap: On".to_string(),
+            MenuItem::Wrap => "Screen wrap: Off".to_string(),
+            MenuItem::Bounce if config.physics.elastic_collisions => {
+                "Asteroid bounces: On".to_string()
+            }
+            MenuItem::Bounce => "Asteroid bounces: Off".to_string(),
+            MenuItem::Back => "Back".to_string(),
+        }
+    }
+}
+
+/// The page shown while in `MainMenu` or `Paused`.
+#[derive(Resource, Debug)]
+struct Menu {
+    page: MenuPage,
+    selected: usize,
+}
+
+impl Menu {
+    fn open(page: MenuPage) -> Self {
+        Self { page, selected: 0 }
+    }
+}
+
+#[derive(Component, Debug)]
+struct MenuScreen;
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::MainMenu), open_main_menu)
+            .add_systems(OnEnter(GameState::Paused), open_pause_menu)
+            .add_systems(OnExit(GameState::MainMenu), close_menu)
+            .add_systems(OnExit(GameState::Paused), close_menu)
+            .add_systems(
+                Update,
+                (navigate_menu, draw_menu)
+                    .chain()
+                    .run_if(resource_exists::<Menu>()),
+            );
+    }
+}
+
+fn open_main_menu(mut commands: Commands) {
+    commands.insert_resource(Menu::open(MenuPage::Main));
+    spawn_menu_screen(&mut commands, GameState::MainMenu);
+}
+
+fn open_pause_menu(mut commands: Commands) {
+    commands.insert_resource(Menu::open(MenuPage::Pause));
+    spawn_menu_screen(&mut commands, GameState::Paused);
+}
+
+fn spawn_menu_screen(commands: &mut Commands, state: GameState) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(16.0),
+                ..default()
+            },
+            background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+            ..default()
+        },
+        Name::new("MenuScreen"),
+        StateScoped(state),
+        MenuScreen,
+    ));
+}
+
+fn close_menu(mut commands: Commands) {
+    commands.remove_resource::<Menu>();
+}
+
+fn navigate_menu(
+    mut commands: Commands,
+    mut menu: ResMut<Menu>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut app_exit_event_writer: EventWriter<AppExit>,
+    mut config: ResMut<GameConfig>,
+    state: Res<State<GameState>>,
+    action_state: Res<ActionState>,
+) {
+    let items = menu.page.items();
+    let home_page = if *state.get() == GameState::Paused {
+        MenuPage::Pause
+    } else {
+        MenuPage::Main
+    };
+
+    if action_state.just_pressed(Action::MenuUp) {
+        menu.selected = (menu.selected + items.len() - 1) % items.len();
+    }
+    if action_state.just_pressed(Action::MenuDown) {
+        menu.selected = (menu.selected + 1) % items.len();
+    }
+
+    if action_state.just_pressed(Action::Back) || action_state.just_pressed(Action::Pause) {
+        match menu.page {
+            MenuPage::Main => (),
+            MenuPage::Pause => next_state.set(GameState::InGame),
+            MenuPage::Settings => *menu = Menu::open(home_page),
+        }
+        return;
+    }
+    if !action_state.just_pressed(Action::Confirm) {
+        return;
+    }
+
+    match items[menu.selected] {
+        MenuItem::Play | MenuItem::Resume => next_state.set(GameState::InGame),
+        MenuItem::Restart => {
+            commands.add(restart_run);
+            next_state.set(GameState::InGame);
+        }
+        MenuItem::Settings => *menu = Menu::open(MenuPage::Settings),
+        MenuItem::Quit if *state.get() == GameState::Paused => {
+            next_state.set(GameState::MainMenu);
+        }
+        MenuItem::Quit => app_exit_event_writer.send(AppExit),
+        // settings last until the config file is reloaded
+        MenuItem::FlightModel => {
+            config.spaceship.flight_model = match config.spaceship.flight_model {
+                FlightModel::Arcade => FlightModel::Newtonian,
+                FlightModel::Newtonian => FlightModel::Arcade,
+            };
+        }
+        MenuItem::Wrap => config.playfield.wrap = !config.playfield.wrap,
+        MenuItem::Bounce => {
+            config.physics.elastic_collisions = !config.physics.elastic_collisions;
+        }
+        MenuItem::Back => *menu = Menu::open(home_page),
+    }
+}
+
+fn draw_menu(
+    mut commands: Commands,
+    menu: Res<Menu>,
+    query: Query<Entity, With<MenuScreen>>,
+    state: Res<State<GameState>>,
+    config: Res<GameConfig>,
+) {
+    if !menu.is_changed() && !config.is_changed() {
+        return;
+    }
+    let text_style = |font_size, color| TextStyle {
+        font_size,
+        color,
+        ..default()
+    };
+
+    for entity in query.iter() {
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    menu.page.title(),
+                    text_style(TITLE_FONT_SIZE, Color::WHITE),
+                ));
+                for (index, item) in menu.page.items().iter().enumerate() {
+                    let label = item.label(state.get(), &config);
+                    let section = if index == menu.selected {
+                        TextBundle::from_section(
+                            format!("> {label} <"),
+                            text_style(ITEM_FONT_SIZE, SELECTED_COLOR),
+                        )
+                    } else {
+                        TextBundle::from_section(label, text_style(ITEM_FONT_SIZE, Color::WHITE))
+                    };
+                    parent.spawn(section);
+                }
+            });
+    }
+}
@@ -24,12 +24,24 @@ impl Acceleration {
     }
 }
 
+/// How hard the entity is to push around in collisions.
+#[derive(Component, Debug)]
+pub struct Mass {
+    pub value: f32,
+}
+
+impl Mass {
+    pub fn new(value: f32) -> Self {
+        Self { value }
+    }
+}
+
 pub struct MovementPlugin;
 
 impl Plugin for MovementPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
-            Update,
+            FixedUpdate,
             (update_velocity, update_position)
                 .chain()
                 .in_set(InGameSet::EntityUpdates),
@@ -43,7 +55,7 @@ fn update_velocity(mut query: Query<(&Acceleration, &mut Velocity)>, time: Res<T
     }
 }
 
-fn update_position(mut query: Query<(&Velocity, &mut Transform)>, time: Res<Time>) {
+pub fn update_position(mut query: Query<(&Velocity, &mut Transform)>, time: Res<Time>) {
     for (velocity, mut transform) in &mut query {
         transform.translation += velocity.value * time.delta_seconds();
     }
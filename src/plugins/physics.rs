@@ -0,0 +1,228 @@
+use bevy::prelude::*;
+
+use super::{
+    collision_detection::{
+        broad_phase::{self, Body},
+        collision_detection, Collider, CollisionLayer,
+    },
+    movement::{Mass, Velocity},
+    playfield::Wraps,
+    schedule::InGameSet,
+};
+use crate::resources::game_config::GameConfig;
+
+/// Bounces off other bouncing bodies instead of passing through them, when
+/// `physics.elastic_collisions` is on.
+#[derive(Component, Debug)]
+pub struct Bounces;
+
+/// Only moved by its own controls. Other bouncing bodies bounce off it as if
+/// it were infinitely heavy.
+#[derive(Component, Debug)]
+pub struct Immovable;
+
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            resolve_elastic_collisions
+                .after(collision_detection)
+                .in_set(InGameSet::CollisionDetection),
+        );
+    }
+}
+
+type BouncingBody<'a> = (
+    Entity,
+    &'a mut Transform,
+    &'a mut Velocity,
+    &'a Mass,
+    &'a Collider,
+    Has<Wraps>,
+    Has<Immovable>,
+);
+
+/// Pushes overlapping bodies apart and exchanges their velocities along the
+/// line between them, conserving momentum and kinetic energy. Lighter bodies
+/// get moved and deflected more.
+fn resolve_elastic_collisions(
+    mut query: Query<BouncingBody, With<Bounces>>,
+    config: Res<GameConfig>,
+) {
+    if !config.physics.elastic_collisions {
+        return;
+    }
+
+    let mut entities = Vec::new();
+    let mut bodies = Vec::new();
+    let mut wraps = Vec::new();
+    for (entity, transform, _, _, collider, body_wraps, _) in query.iter() {
+        entities.push(entity);
+        bodies.push(Body::new(
+            transform.translation,
+            collider.radius,
+            CollisionLayer::ALL,
+            CollisionLayer::ALL,
+        ));
+        wraps.push(body_wraps);
+    }
+    let pairs = if config.playfield.wrap {
+        broad_phase::wrapped_overlapping_pairs(&bodies, &wraps, config.playfield.half_extents)
+    } else {
+        broad_phase::overlapping_pairs(&bodies)
+    };
+
+    for (i, j) in pairs {
+        let Ok([body, other_body]) = query.get_many_mut([entities[i], entities[j]]) else {
+            continue;
+        };
+        let (_, mut transform, mut velocity, mass, collider, _, immovable) = body;
+        let (
+            _,
+            mut other_transform,
+            mut other_velocity,
+            other_mass,
+            other_collider,
+            _,
+            other_immovable,
+        ) = other_body;
+
+        let offset = config
+            .playfield
+            .offset(transform.translation, other_transform.translation);
+        let distance = offset.length();
+        // an earlier pair may already have pushed these two apart
+        let overlap = collider.radius + other_collider.radius - distance;
+        if overlap <= 0.0 {
+            continue;
+        }
+        let normal = if distance > 0.0 {
+            offset / distance
+        } else {
+            Vec3::X
+        };
+
+        let inverse_mass = if immovable { 0.0 } else { 1.0 / mass.value };
+        let other_inverse_mass = if other_immovable {
+            0.0
+        } else {
+            1.0 / other_mass.value
+        };
+        let total_inverse_mass = inverse_mass + other_inverse_mass;
+        if total_inverse_mass == 0.0 {
+            continue;
+        }
+
+        transform.translation -= normal * overlap * inverse_mass / total_inverse_mass;
+        other_transform.translation += normal * overlap * other_inverse_mass / total_inverse_mass;
+
+        // only bounce bodies that are still closing in on each other
+        let closing_speed = (velocity.value - other_velocity.value).dot(normal);
+        if closing_speed <= 0.0 {
+            continue;
+        }
+        let impulse = 2.0 * closing_speed / total_inverse_mass;
+        velocity.value -= normal * impulse * inverse_mass;
+        other_velocity.value += normal * impulse * other_inverse_mass;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        plugins::{
+            collision_detection::CollisionDetectionPlugin,
+            movement::MovementPlugin,
+            spaceship::{Spaceship, SpaceshipPlugin},
+        },
+        resources::{asset_loader::SceneAssets, game_config::FlightModel},
+        state::GameState,
+        test_utils::{enter_state, headless_app},
+    };
+
+    fn physics_app() -> App {
+        let mut app = headless_app();
+        app.add_plugins((CollisionDetectionPlugin, PhysicsPlugin));
+        app
+    }
+
+    fn spawn_bouncing(app: &mut App, translation: Vec3, velocity: Vec3, mass: f32) -> Entity {
+        app.world
+            .spawn((
+                Transform::from_translation(translation),
+                Velocity::new(velocity),
+                Mass::new(mass),
+                Collider::new(1.0, CollisionLayer::ENEMY, CollisionLayer::ENEMY),
+                Bounces,
+            ))
+            .id()
+    }
+
+    fn momentum(app: &mut App) -> Vec3 {
+        app.world
+            .query::<(&Velocity, &Mass)>()
+            .iter(&app.world)
+            .map(|(velocity, mass)| velocity.value * mass.value)
+            .sum()
+    }
+
+    #[test]
+    fn bounces_conserve_momentum() {
+        let mut app = physics_app();
+        enter_state(&mut app, GameState::InGame);
+        let light = spawn_bouncing(
+            &mut app,
+            Vec3::new(-0.9, 0.0, 0.0),
+            Vec3::new(4.0, 0.0, 1.0),
+            1.0,
+        );
+        let heavy = spawn_bouncing(
+            &mut app,
+            Vec3::new(0.9, 0.0, 0.0),
+            Vec3::new(-1.0, 0.0, 0.0),
+            3.0,
+        );
+        let before = momentum(&mut app);
+
+        app.update();
+
+        assert!(momentum(&mut app).abs_diff_eq(before, 1e-4));
+        // the light body got turned around, the heavy one pushed back
+        assert!(app.world.get::<Velocity>(light).unwrap().value.x < 0.0);
+        assert!(app.world.get::<Velocity>(heavy).unwrap().value.x > 0.0);
+    }
+
+    #[test]
+    fn arcade_spaceship_is_not_pushed() {
+        let mut app = physics_app();
+        app.add_plugins((SpaceshipPlugin, MovementPlugin))
+            .init_resource::<SceneAssets>();
+        app.world
+            .resource_mut::<GameConfig>()
+            .spaceship
+            .flight_model = FlightModel::Arcade;
+        enter_state(&mut app, GameState::MainMenu);
+        enter_state(&mut app, GameState::InGame);
+
+        let mut spaceships = app
+            .world
+            .query_filtered::<(Entity, &Transform), With<Spaceship>>();
+        let (spaceship, transform) = spaceships.single(&app.world);
+        let start = transform.translation;
+        assert!(app.world.get::<Immovable>(spaceship).is_some());
+        let asteroid = spawn_bouncing(&mut app, start - Vec3::X, Vec3::new(20.0, 0.0, 0.0), 10.0);
+
+        for _ in 0..10 {
+            app.update();
+        }
+
+        assert_eq!(
+            app.world.get::<Transform>(spaceship).unwrap().translation,
+            start
+        );
+        assert!(app.world.get::<Velocity>(asteroid).unwrap().value.x < 0.0);
+    }
+}
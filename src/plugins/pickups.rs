@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+use serde::Deserialize;
+
+use super::{
+    asteroids::{split_dead_asteroids, Asteroid, AsteroidSize},
+    collision_detection::{handle_collisions, Collider, CollisionEvent, CollisionLayer},
+    despawn::Lifetime,
+    interpolation::TransformInterpolation,
+    movement::{Acceleration, Velocity},
+    playfield::Wraps,
+    rotation::RotationVelocity,
+    schedule::InGameSet,
+    shield::Shield,
+    spaceship::{Lives, Spaceship},
+    weapon::WeaponModifiers,
+};
+use crate::{
+    bundles::moving_object::MovingObjectBundle,
+    health::Health,
+    resources::{
+        asset_loader::SceneAssets,
+        game_config::{DropTable, GameConfig},
+        rng::GameRng,
+    },
+    state::{GameState, StateScoped},
+};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum PickupKind {
+    Repair,
+    ShieldRecharge,
+    RapidFire,
+    SpreadShot,
+    ExtraLife,
+}
+
+impl DropTable {
+    /// Rolls the table, returning what dropped if anything did.
+    fn roll(&self, rng: &mut impl Rng) -> Option<PickupKind> {
+        if !rng.gen_bool(self.chance.into()) {
+            return None;
+        }
+        let index = WeightedIndex::new(self.weights.iter().map(|&(_, weight)| weight)).ok()?;
+        Some(self.weights[index.sample(rng)].0)
+    }
+}
+
+pub struct PickupPlugin;
+
+impl Plugin for PickupPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            drop_pickups
+                .after(split_dead_asteroids)
+                .in_set(InGameSet::EntityDeaths),
+        )
+        .add_systems(
+            FixedUpdate,
+            collect_pickups
+                .after(handle_collisions)
+                .in_set(InGameSet::EntityUpdates),
+        );
+    }
+}
+
+fn drop_pickups(
+    mut commands: Commands,
+    query: Query<(&Transform, &Health, &AsteroidSize), With<Asteroid>>,
+    scene_assets: Res<SceneAssets>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+) {
+    for (transform, health, &size) in query.iter() {
+        if !health.is_dead() {
+            continue;
+        }
+        let Some(kind) = config.asteroids.size(size).drops.roll(&mut *rng) else {
+            continue;
+        };
+
+        let config = &config.pickups;
+        let direction =
+            Vec3::new(rng.gen_range(-1.0..1.0), 0., rng.gen_range(-1.0..1.0)).normalize_or_zero();
+        let rotation = Vec3::new(
+            0.0,
+            rng.gen_range(-config.max_rotation_speed..config.max_rotation_speed),
+            0.0,
+        );
+        commands.spawn((
+            MovingObjectBundle {
+                interpolation: TransformInterpolation::default(),
+                velocity: Velocity::new(direction * config.drift_speed),
+                acceleration: Acceleration::new(Vec3::ZERO),
+                rotation_velocity: RotationVelocity::new(rotation),
+                model: SceneBundle {
+                    scene: scene_assets.missiles.clone(),
+                    transform: Transform::from_translation(transform.translation)
+                        .with_scale(Vec3::splat(config.scale)),
+                    ..default()
+                },
+                collider: Collider::new(
+                    config.collider_radius,
+                    CollisionLayer::PICKUP,
+                    CollisionLayer::PLAYER,
+                ),
+            },
+            Name::new("Pickup"),
+            StateScoped(GameState::InGame),
+            kind,
+            Wraps,
+            Lifetime::from_seconds(config.lifetime_seconds),
+        ));
+    }
+}
+
+fn collect_pickups(
+    mut commands: Commands,
+    mut collision_event_reader: EventReader<CollisionEvent>,
+    mut lives: ResMut<Lives>,
+    pickup_query: Query<&PickupKind>,
+    mut spaceship_query: Query<(&mut Health, &mut Shield, &mut WeaponModifiers), With<Spaceship>>,
+    config: Res<GameConfig>,
+) {
+    for &CollisionEvent {
+        entity,
+        collided_entity,
+    } in collision_event_reader.read()
+    {
+        let Ok(&kind) = pickup_query.get(entity) else {
+            continue;
+        };
+        let Ok((mut health, mut shield, mut modifiers)) = spaceship_query.get_mut(collided_entity)
+        else {
+            continue;
+        };
+
+        let pickups = &config.pickups;
+        match kind {
+            PickupKind::Repair => {
+                health.value = (health.value + pickups.repair_amount).min(config.spaceship.health);
+            }
+            PickupKind::ShieldRecharge => shield.recharge(pickups.shield_recharge_amount),
+            PickupKind::RapidFire => modifiers.add_rapid_fire(pickups.rapid_fire),
+            PickupKind::SpreadShot => modifiers.add_spread_shot(pickups.spread_shot),
+            PickupKind::ExtraLife => {
+                lives.remaining = (lives.remaining + 1).min(pickups.max_lives);
+            }
+        }
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+
+use super::{
+    interpolation::TransformInterpolation, movement::update_position, schedule::InGameSet,
+};
+use crate::resources::game_config::{GameConfig, PlayfieldConfig};
+
+/// Leaves the playfield on one side and comes back on the other, when the
+/// playfield wraps. Anything else that moves is despawned once it leaves.
+#[derive(Component, Debug)]
+pub struct Wraps;
+
+impl PlayfieldConfig {
+    /// The way from `from` to `to`, taking the shortest way across the edges
+    /// when the playfield wraps.
+    pub fn offset(&self, from: Vec3, to: Vec3) -> Vec3 {
+        if self.wrap {
+            self.wrap_translation(to - from)
+        } else {
+            to - from
+        }
+    }
+}
+
+pub struct PlayfieldPlugin;
+
+impl Plugin for PlayfieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            wrap_entities
+                .after(update_position)
+                .in_set(InGameSet::EntityUpdates),
+        );
+    }
+}
+
+fn wrap_entities(
+    mut query: Query<(&mut Transform, Option<&mut TransformInterpolation>), With<Wraps>>,
+    config: Res<GameConfig>,
+) {
+    let playfield = &config.playfield;
+    if !playfield.wrap {
+        return;
+    }
+
+    for (mut transform, interpolation) in query.iter_mut() {
+        let wrapped = playfield.wrap_translation(transform.translation);
+        if wrapped == transform.translation {
+            continue;
+        }
+        if let Some(mut interpolation) = interpolation {
+            interpolation.teleport(wrapped - transform.translation);
+        }
+        transform.translation = wrapped;
+    }
+}
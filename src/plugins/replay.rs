@@ -0,0 +1,302 @@
+use std::{
+    collections::VecDeque,
+    env,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use bevy::{
+    prelude::*,
+    time::{TimeSystem, TimeUpdateStrategy},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::actions::{update_action_state, ActionState};
+use crate::resources::{game_config::GameConfig, rng::GameRng};
+
+const RECORD_ARG: &str = "--record";
+const REPLAY_ARG: &str = "--replay";
+
+/// First line of a replay file.
+#[derive(Debug, Serialize, Deserialize)]
+struct ReplayHeader {
+    seed: u64,
+}
+
+/// One line per frame after the header, flushed as it's written so a crash
+/// still leaves every frame up to it on disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct ReplayFrame {
+    delta: Duration,
+    actions: ActionState,
+}
+
+#[derive(Debug, Error)]
+enum ReplayError {
+    #[error("could not access replay file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("malformed replay file on line {line}: {error}")]
+    Ron {
+        line: usize,
+        error: ron::error::SpannedError,
+    },
+    #[error("replay file is empty")]
+    Empty,
+    #[error("could not write replay frame: {0}")]
+    Serialize(#[from] ron::Error),
+}
+
+#[derive(Resource)]
+struct ReplayRecorder {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+}
+
+#[derive(Resource)]
+struct ReplayPlayer {
+    seed: u64,
+    frames: VecDeque<ReplayFrame>,
+    current: Option<ReplayFrame>,
+}
+
+/// Records every frame's `ActionState` and delta time with `--record <path>`,
+/// and plays such a file back instead of the player's input with
+/// `--replay <path>`.
+///
+/// Both start on the first frame after the game config is available, with a
+/// freshly seeded `GameRng` and an empty fixed-timestep accumulator, so the
+/// asset loading time doesn't leak into the run.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(path) = arg_value(REPLAY_ARG) {
+            match load_replay(&path) {
+                Ok(player) => {
+                    info!("Replaying {} frames from {path:?}", player.frames.len());
+                    app.insert_resource(player);
+                }
+                Err(error) => error!("Failed to load replay {path:?}: {error}"),
+            }
+        } else if let Some(path) = arg_value(RECORD_ARG) {
+            app.insert_resource(ReplayRecorder { path, writer: None });
+        }
+
+        app.add_systems(
+            First,
+            (
+                start_recording.run_if(resource_exists::<ReplayRecorder>()),
+                advance_replay.run_if(resource_exists::<ReplayPlayer>()),
+            )
+                .run_if(resource_exists::<GameConfig>())
+                .before(TimeSystem),
+        )
+        .add_systems(
+            PreUpdate,
+            apply_replayed_actions
+                .after(update_action_state)
+                .run_if(resource_exists::<ReplayPlayer>()),
+        )
+        .add_systems(
+            Last,
+            record_frame.run_if(resource_exists::<ReplayRecorder>()),
+        );
+    }
+}
+
+fn arg_value(name: &str) -> Option<PathBuf> {
+    env::args()
+        .skip_while(|arg| arg != name)
+        .nth(1)
+        .map(PathBuf::from)
+}
+
+fn load_replay(path: &PathBuf) -> Result<ReplayPlayer, ReplayError> {
+    let contents = fs::read_to_string(path)?;
+    let mut lines = contents.lines().enumerate();
+    let parse_error = |line: usize| {
+        move |error| ReplayError::Ron {
+            line: line + 1,
+            error,
+        }
+    };
+
+    let (line, header) = lines.next().ok_or(ReplayError::Empty)?;
+    let header: ReplayHeader = ron::from_str(header).map_err(parse_error(line))?;
+    let frames = lines
+        .map(|(line, frame)| ron::from_str(frame).map_err(parse_error(line)))
+        .collect::<Result<_, _>>()?;
+
+    Ok(ReplayPlayer {
+        seed: header.seed,
+        frames,
+        current: None,
+    })
+}
+
+fn restart_simulation(rng: &mut GameRng, seed: u64, fixed_time: &mut Time<Fixed>) {
+    *rng = GameRng::from_seed(seed);
+    *fixed_time = Time::<Fixed>::from_duration(fixed_time.timestep());
+}
+
+fn start_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut rng: ResMut<GameRng>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut commands: Commands,
+) {
+    if recorder.writer.is_some() {
+        return;
+    }
+
+    let seed = rng.seed;
+    let result = File::create(&recorder.path)
+        .map_err(ReplayError::from)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            writeln!(writer, "{}", ron::to_string(&ReplayHeader { seed })?)?;
+            Ok(writer)
+        });
+
+    match result {
+        Ok(writer) => {
+            info!("Recording replay to {:?}", recorder.path);
+            recorder.writer = Some(writer);
+            restart_simulation(&mut rng, seed, &mut fixed_time);
+        }
+        Err(error) => {
+            warn!("Failed to record replay to {:?}: {error}", recorder.path);
+            commands.remove_resource::<ReplayRecorder>();
+        }
+    }
+}
+
+fn record_frame(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    action_state: Res<ActionState>,
+) {
+    let Some(writer) = recorder.writer.as_mut() else {
+        return;
+    };
+
+    let frame = ReplayFrame {
+        delta: time.delta(),
+        actions: action_state.clone(),
+    };
+    let result = ron::to_string(&frame)
+        .map_err(ReplayError::from)
+        .and_then(|line| {
+            writeln!(writer, "{line}")?;
+            Ok(writer.flush()?)
+        });
+
+    if let Err(error) = result {
+        warn!("Stopped recording replay to {:?}: {error}", recorder.path);
+        commands.remove_resource::<ReplayRecorder>();
+    }
+}
+
+fn advance_replay(
+    mut player: ResMut<ReplayPlayer>,
+    mut rng: ResMut<GameRng>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    mut commands: Commands,
+) {
+    if player.current.is_none() {
+        let seed = player.seed;
+        restart_simulation(&mut rng, seed, &mut fixed_time);
+    }
+
+    player.current = player.frames.pop_front();
+    match &player.current {
+        Some(frame) => *time_update_strategy = TimeUpdateStrategy::ManualDuration(frame.delta),
+        None => {
+            info!("Replay finished, handing control back to the player");
+            *time_update_strategy = TimeUpdateStrategy::Automatic;
+            commands.remove_resource::<ReplayPlayer>();
+        }
+    }
+}
+
+fn apply_replayed_actions(player: Res<ReplayPlayer>, mut action_state: ResMut<ActionState>) {
+    if let Some(frame) = &player.current {
+        *action_state = frame.actions.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        plugins::{
+            actions::{Action, ActionAxis},
+            asteroids::Asteroid,
+            score::{Score, ScorePlugin},
+            spaceship::Spaceship,
+        },
+        state::GameState,
+        test_utils::{add_gameplay_plugins, enter_state, headless_app},
+    };
+
+    /// Five seconds of play.
+    const STEPS: usize = 300;
+
+    /// Plays `STEPS` fixed steps with either a recorder or a player in place
+    /// and returns the score and where the spaceship and asteroids ended up.
+    fn play(replay: impl Resource, actions: Option<ActionState>) -> (u32, Vec<Transform>) {
+        let mut app = headless_app();
+        add_gameplay_plugins(&mut app);
+        app.add_plugins((ScorePlugin, ReplayPlugin))
+            .insert_resource(GameRng::from_seed(1))
+            .insert_resource(replay);
+        enter_state(&mut app, GameState::MainMenu);
+        enter_state(&mut app, GameState::InGame);
+
+        if let Some(actions) = actions {
+            *app.world.resource_mut::<ActionState>() = actions;
+        }
+        for _ in 0..STEPS {
+            app.update();
+        }
+
+        let mut transforms: Vec<Transform> = app
+            .world
+            .query_filtered::<&Transform, With<Spaceship>>()
+            .iter(&app.world)
+            .copied()
+            .collect();
+        transforms.extend(
+            app.world
+                .query_filtered::<&Transform, With<Asteroid>>()
+                .iter(&app.world),
+        );
+        (app.world.resource::<Score>().points, transforms)
+    }
+
+    #[test]
+    fn replays_play_out_like_the_recording() {
+        let path = env::temp_dir().join(format!("replay-{}.ron", std::process::id()));
+        let mut actions = ActionState::default();
+        actions.press(Action::Fire);
+        actions.set_axis(ActionAxis::Turn, 0.3);
+        actions.set_axis(ActionAxis::Thrust, 0.5);
+
+        let recorder = ReplayRecorder {
+            path: path.clone(),
+            writer: None,
+        };
+        let recorded = play(recorder, Some(actions));
+        let player = load_replay(&path).expect("the recorded replay should load");
+        fs::remove_file(&path).ok();
+        assert_eq!(player.frames.len(), STEPS + 2);
+        let replayed = play(player, None);
+
+        assert_eq!(recorded, replayed);
+    }
+}
@@ -17,7 +17,10 @@ pub struct RotationPlugin;
 
 impl Plugin for RotationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, update_rotation.in_set(InGameSet::EntityUpdates));
+        app.add_systems(
+            FixedUpdate,
+            update_rotation.in_set(InGameSet::EntityUpdates),
+        );
     }
 }
 
@@ -2,11 +2,16 @@ use bevy::prelude::*;
 
 use crate::state::GameState;
 
+const FIXED_TIMESTEP_HZ: f64 = 60.0;
+
 #[derive(SystemSet, Hash, PartialEq, Eq, Clone, Debug)]
 pub enum InGameSet {
     UserInput,
     EntityUpdates,
     CollisionDetection,
+    /// Runs right before dead entities are despawned, while their components
+    /// can still be read.
+    EntityDeaths,
     DespawnEntities,
 }
 
@@ -14,22 +19,24 @@ pub struct SchedulePlugin;
 
 impl Plugin for SchedulePlugin {
     fn build(&self, app: &mut App) {
-        app.configure_sets(
-            Update,
-            (
-                InGameSet::DespawnEntities,
-                InGameSet::UserInput,
-                InGameSet::EntityUpdates,
-                InGameSet::CollisionDetection,
+        app.insert_resource(Time::<Fixed>::from_hz(FIXED_TIMESTEP_HZ))
+            .configure_sets(
+                FixedUpdate,
+                (
+                    InGameSet::EntityDeaths,
+                    InGameSet::DespawnEntities,
+                    InGameSet::UserInput,
+                    InGameSet::EntityUpdates,
+                    InGameSet::CollisionDetection,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame)),
             )
-                .chain()
-                .run_if(in_state(GameState::InGame)),
-        )
-        .add_systems(
-            Update,
-            apply_deferred
-                .after(InGameSet::DespawnEntities)
-                .before(InGameSet::UserInput),
-        );
+            .add_systems(
+                FixedUpdate,
+                apply_deferred
+                    .after(InGameSet::DespawnEntities)
+                    .before(InGameSet::UserInput),
+            );
     }
 }
@@ -0,0 +1,175 @@
+use std::fs;
+
+use bevy::prelude::*;
+
+use super::{
+    schedule::InGameSet,
+    spaceship::Spaceship,
+    waves::{run_wave_director, WaveClearedEvent},
+};
+use crate::{
+    health::Health,
+    resources::game_config::GameConfig,
+    state::{GameState, NewRun},
+};
+
+const HIGH_SCORES_PATH: &str = "high_scores.txt";
+const HIGH_SCORE_TABLE_SIZE: usize = 10;
+
+/// Points awarded to the player for destroying the entity.
+#[derive(Component, Debug)]
+pub struct ScoreValue {
+    pub points: u32,
+}
+
+impl ScoreValue {
+    pub fn new(points: u32) -> Self {
+        Self { points }
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct KillEvent {
+    pub killer: Entity,
+    pub victim: Entity,
+}
+
+impl KillEvent {
+    pub fn new(killer: Entity, victim: Entity) -> Self {
+        Self { killer, victim }
+    }
+}
+
+#[derive(Resource, Debug)]
+pub struct Score {
+    pub points: u32,
+    pub combo: u32,
+    combo_timer: Timer,
+}
+
+impl Score {
+    pub fn new(combo_window_seconds: f32) -> Self {
+        Self {
+            points: 0,
+            combo: 1,
+            combo_timer: Timer::from_seconds(combo_window_seconds, TimerMode::Once),
+        }
+    }
+}
+
+/// Best scores so far, highest first.
+#[derive(Resource, Debug, Default)]
+pub struct HighScores {
+    pub scores: Vec<u32>,
+}
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Score::new(0.0))
+            .init_resource::<HighScores>()
+            .add_event::<KillEvent>()
+            .add_systems(Startup, load_high_scores)
+            .add_systems(
+                FixedUpdate,
+                (send_kill_events, award_kill_points)
+                    .chain()
+                    .in_set(InGameSet::EntityDeaths),
+            )
+            .add_systems(
+                FixedUpdate,
+                (decay_combo, award_wave_clear_bonus.after(run_wave_director))
+                    .in_set(InGameSet::EntityUpdates),
+            )
+            .add_systems(OnEnter(GameState::GameOver), save_high_score)
+            .add_systems(NewRun, reset_score);
+    }
+}
+
+fn send_kill_events(
+    mut kill_event_writer: EventWriter<KillEvent>,
+    query: Query<(Entity, &Health)>,
+) {
+    for (entity, health) in query.iter() {
+        if !health.is_dead() {
+            continue;
+        }
+        if let Some(killer) = health.last_attacker {
+            kill_event_writer.send(KillEvent::new(killer, entity));
+        }
+    }
+}
+
+fn award_kill_points(
+    mut kill_event_reader: EventReader<KillEvent>,
+    mut score: ResMut<Score>,
+    spaceship_query: Query<(), With<Spaceship>>,
+    score_value_query: Query<&ScoreValue>,
+    config: Res<GameConfig>,
+) {
+    for &KillEvent { killer, victim } in kill_event_reader.read() {
+        if spaceship_query.get(killer).is_err() {
+            continue;
+        }
+        let Ok(score_value) = score_value_query.get(victim) else {
+            continue;
+        };
+        score.points += score_value.points * score.combo;
+        score.combo = (score.combo + 1).min(config.score.max_combo_multiplier);
+        score.combo_timer.reset();
+    }
+}
+
+fn award_wave_clear_bonus(
+    mut wave_cleared_event_reader: EventReader<WaveClearedEvent>,
+    mut score: ResMut<Score>,
+    config: Res<GameConfig>,
+) {
+    for &WaveClearedEvent { wave } in wave_cleared_event_reader.read() {
+        score.points += config.score.wave_clear_bonus_points * wave;
+    }
+}
+
+fn decay_combo(mut score: ResMut<Score>, time: Res<Time>) {
+    // the HUD only cares when the combo runs out, not about every tick
+    let finished = score
+        .bypass_change_detection()
+        .combo_timer
+        .tick(time.delta())
+        .just_finished();
+    if finished && score.combo != 1 {
+        score.combo = 1;
+    }
+}
+
+fn reset_score(mut score: ResMut<Score>, config: Res<GameConfig>) {
+    *score = Score::new(config.score.combo_window_seconds);
+}
+
+fn load_high_scores(mut high_scores: ResMut<HighScores>) {
+    let Ok(contents) = fs::read_to_string(HIGH_SCORES_PATH) else {
+        return;
+    };
+    high_scores.scores = contents
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect();
+    high_scores.scores.sort_unstable_by(|a, b| b.cmp(a));
+    high_scores.scores.truncate(HIGH_SCORE_TABLE_SIZE);
+}
+
+fn save_high_score(score: Res<Score>, mut high_scores: ResMut<HighScores>) {
+    high_scores.scores.push(score.points);
+    high_scores.scores.sort_unstable_by(|a, b| b.cmp(a));
+    high_scores.scores.truncate(HIGH_SCORE_TABLE_SIZE);
+
+    let contents: String = high_scores
+        .scores
+        .iter()
+        .map(|score| format!("{score}\n"))
+        .collect();
+    if let Err(error) = fs::write(HIGH_SCORES_PATH, contents) {
+        warn!("Failed to save high scores to {HIGH_SCORES_PATH}: {error}");
+    }
+}
@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use super::{demage::apply_demage, schedule::InGameSet};
+
+#[derive(Component, Debug)]
+pub struct Shield {
+    pub capacity: f32,
+    pub energy: f32,
+    pub drain_rate: f32,
+    pub recharge_rate: f32,
+    pub active: bool,
+    cooldown: Timer,
+}
+
+impl Shield {
+    pub fn new(capacity: f32, drain_rate: f32, recharge_rate: f32, cooldown_seconds: f32) -> Self {
+        let mut cooldown = Timer::from_seconds(cooldown_seconds, TimerMode::Once);
+        // A fresh shield is usable straight away.
+        cooldown.tick(cooldown.duration());
+        Self {
+            capacity,
+            energy: capacity,
+            drain_rate,
+            recharge_rate,
+            active: false,
+            cooldown,
+        }
+    }
+
+    pub fn is_cooling_down(&self) -> bool {
+        !self.cooldown.finished()
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.active && self.energy > 0.0
+    }
+
+    /// Raises or lowers the shield. Raising fails while the shield is
+    /// recovering from being depleted.
+    pub fn set_active(&mut self, active: bool) {
+        self.active = active && !self.is_cooling_down() && self.energy > 0.0;
+    }
+
+    /// Soaks up as much of `amount` as the remaining energy allows and returns
+    /// the part that got through.
+    pub fn absorb(&mut self, amount: f32) -> f32 {
+        if !self.is_up() {
+            return amount;
+        }
+        let absorbed = amount.min(self.energy);
+        self.energy -= absorbed;
+        if self.energy <= 0.0 {
+            self.deplete();
+        }
+        amount - absorbed
+    }
+
+    /// Tops the energy up by `amount`, without ending a cooldown.
+    pub fn recharge(&mut self, amount: f32) {
+        self.energy = (self.energy + amount).min(self.capacity);
+    }
+
+    fn deplete(&mut self) {
+        self.energy = 0.0;
+        self.active = false;
+        self.cooldown.reset();
+    }
+
+    fn tick(&mut self, delta: Duration) {
+        let delta_seconds = delta.as_secs_f32();
+        if self.is_up() {
+            self.energy -= self.drain_rate * delta_seconds;
+            if self.energy <= 0.0 {
+                self.deplete();
+            }
+        } else if self.is_cooling_down() {
+            self.cooldown.tick(delta);
+        } else {
+            self.energy = (self.energy + self.recharge_rate * delta_seconds).min(self.capacity);
+        }
+    }
+}
+
+pub struct ShieldPlugin;
+
+impl Plugin for ShieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            // drain and recharge only after this step's hits were absorbed
+            update_shields
+                .after(apply_demage)
+                .in_set(InGameSet::EntityUpdates),
+        );
+    }
+}
+
+fn update_shields(mut query: Query<&mut Shield>, time: Res<Time>) {
+    for mut shield in &mut query {
+        // only the energy and whether it's up are worth redrawing the HUD
+        // for, not the cooldown ticking
+        let (energy, active) = (shield.energy, shield.active);
+        shield.bypass_change_detection().tick(time.delta());
+        if shield.energy != energy || shield.active != active {
+            shield.set_changed();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        health::Health,
+        plugins::{
+            collision_detection::{
+                Collider, CollisionDemage, CollisionDetectionPlugin, CollisionEvent, CollisionLayer,
+            },
+            demage::{DemagePlugin, DemageType},
+        },
+        state::GameState,
+        test_utils::{enter_state, headless_app},
+    };
+
+    /// A raised `shield` on a target with 100 health, in a running game.
+    fn shielded_target(mut shield: Shield) -> (App, Entity) {
+        let mut app = headless_app();
+        app.add_plugins(CollisionDetectionPlugin)
+            .add_plugins(DemagePlugin)
+            .add_plugins(ShieldPlugin);
+        enter_state(&mut app, GameState::InGame);
+
+        shield.set_active(true);
+        let target = app
+            .world
+            .spawn((
+                Transform::default(),
+                Collider::new(1.0, CollisionLayer::PLAYER, CollisionLayer::ENEMY),
+                Health::new(100.0),
+                shield,
+            ))
+            .id();
+        (app, target)
+    }
+
+    /// Hits `target` once with `amount` from a fresh source, so hit cooldowns
+    /// don't get in the way.
+    fn hit(app: &mut App, target: Entity, amount: f32) {
+        let source = app
+            .world
+            .spawn((
+                Transform::from_xyz(10.0, 0.0, 0.0),
+                Collider::new(1.0, CollisionLayer::ENEMY, CollisionLayer::PLAYER),
+                CollisionDemage::new(amount, DemageType::Kinetic),
+            ))
+            .id();
+        app.world.send_event(CollisionEvent::new(target, source));
+        app.update();
+    }
+
+    fn health(app: &App, target: Entity) -> f32 {
+        app.world.get::<Health>(target).unwrap().value
+    }
+
+    fn shield(app: &App, target: Entity) -> &Shield {
+        app.world.get::<Shield>(target).unwrap()
+    }
+
+    #[test]
+    fn raised_shield_absorbs_collision_demage() {
+        let (mut app, target) = shielded_target(Shield::new(100.0, 0.0, 0.0, 1.0));
+
+        hit(&mut app, target, 30.0);
+
+        assert_eq!(health(&app, target), 100.0);
+        assert_eq!(shield(&app, target).energy, 70.0);
+        assert!(shield(&app, target).is_up());
+    }
+
+    #[test]
+    fn depleted_shield_cools_down() {
+        let (mut app, target) = shielded_target(Shield::new(20.0, 0.0, 0.0, 1.0));
+
+        hit(&mut app, target, 20.0);
+
+        let shield = app.world.get_mut::<Shield>(target).unwrap().into_inner();
+        assert_eq!(shield.energy, 0.0);
+        assert!(!shield.is_up());
+        assert!(shield.is_cooling_down());
+        shield.set_active(true);
+        assert!(!shield.active);
+    }
+
+    #[test]
+    fn demage_gets_through_a_depleted_shield() {
+        let (mut app, target) = shielded_target(Shield::new(20.0, 0.0, 0.0, 1.0));
+
+        // the shield soaks up what it can and lets the rest through
+        hit(&mut app, target, 30.0);
+        assert_eq!(health(&app, target), 90.0);
+
+        hit(&mut app, target, 30.0);
+        assert_eq!(health(&app, target), 60.0);
+    }
+
+    #[test]
+    fn shield_recharges_after_the_cooldown() {
+        // half a second of cooldown, then two seconds to charge back up
+        let (mut app, target) = shielded_target(Shield::new(20.0, 0.0, 10.0, 0.5));
+        hit(&mut app, target, 20.0);
+
+        for _ in 0..25 {
+            app.update();
+        }
+        assert_eq!(shield(&app, target).energy, 0.0);
+        assert!(shield(&app, target).is_cooling_down());
+
+        for _ in 0..10 {
+            app.update();
+        }
+        assert!(!shield(&app, target).is_cooling_down());
+        assert!(shield(&app, target).energy > 0.0);
+
+        for _ in 0..120 {
+            app.update();
+        }
+        assert_eq!(shield(&app, target).energy, 20.0);
+        let mut shield = app.world.get_mut::<Shield>(target).unwrap();
+        shield.set_active(true);
+        assert!(shield.is_up());
+    }
+}
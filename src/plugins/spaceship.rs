@@ -1,30 +1,31 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::EntityCommands, prelude::*};
 
 // project internal
 use super::{
-    collision_detection::{Collider, CollisionDemage},
-    movement::{Acceleration, Velocity},
+    actions::{Action, ActionAxis, ActionState},
+    collision_detection::{Collider, CollisionDemage, CollisionLayer},
+    demage::DemageType,
+    homing::Homing,
+    interpolation::TransformInterpolation,
+    movement::{Acceleration, Mass, Velocity},
+    physics::{Bounces, Immovable},
+    playfield::Wraps,
     rotation::RotationVelocity,
     schedule::InGameSet,
+    shield::Shield,
+    weapon::{projectile_bundle, Weapon, WeaponModifiers},
 };
 use crate::{
-    bundles::moving_object::MovingObjectBundle, health::Health,
-    resources::asset_loader::SceneAssets, state::GameState,
+    bundles::moving_object::MovingObjectBundle,
+    health::Health,
+    resources::{
+        asset_loader::SceneAssets,
+        game_config::{FlightModel, GameConfig},
+    },
+    state::{EndRun, GameState, NewRun, StateScoped},
 };
 
-const STARTING_TRANSLATION: Vec3 = Vec3::new(0.0, 0.0, -20.0);
-const SPACESHIP_SPEED: f32 = 25.0;
-const SPACESHIP_ROTATION_SPEED: f32 = 2.5;
-const SPACESHIP_ROLL_SPEED: f32 = 2.5;
-const SPACESHIP_COLLIDER_RADIUS: f32 = 7.0;
-const HEALTH: f32 = 100.0;
-const COLLISION_DEMAGE: f32 = 10.0;
-
-const MISSILE_SPEED: f32 = 20.0;
-const MISSILE_FORWARD_SPAWN_SCALAR: f32 = 7.5;
-const MISSILE_COLLIDER_RADIUS: f32 = 0.03;
-const MISSILE_HEALTH: f32 = 1.0;
-const MISSILE_COLLISION_DEMAGE: f32 = 1.0;
+const BLINK_INTERVAL_SECONDS: f32 = 0.1;
 
 #[derive(Component, Debug)]
 pub struct Spaceship;
@@ -32,17 +33,44 @@ pub struct Spaceship;
 #[derive(Component, Debug)]
 pub struct SpaceshipMissile;
 
+/// Ignores all incoming demage until the timer runs out.
 #[derive(Component, Debug)]
-pub struct SpaceshipShield;
+pub struct Invulnerable {
+    timer: Timer,
+    blink_timer: Timer,
+}
+
+impl Invulnerable {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+            blink_timer: Timer::from_seconds(BLINK_INTERVAL_SECONDS, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Spaceships left, including the one currently flying.
+#[derive(Resource, Debug, Default)]
+pub struct Lives {
+    pub remaining: u32,
+}
+
+#[derive(Resource, Debug)]
+struct RespawnTimer {
+    timer: Timer,
+}
 
 pub struct SpaceshipPlugin;
 
 impl Plugin for SpaceshipPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PostStartup, spawn_spaceship)
+        app.init_resource::<Lives>()
+            .add_systems(NewRun, (spawn_spaceship, reset_lives))
+            .add_systems(EndRun, cancel_respawn)
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
+                    update_spaceship_immovable.run_if(resource_changed::<GameConfig>()),
                     spaceship_movement_controls,
                     spaceship_weapon_controls,
                     spaceship_shield_controls,
@@ -50,117 +78,293 @@ impl Plugin for SpaceshipPlugin {
                     .chain()
                     .in_set(InGameSet::UserInput),
             )
-            .add_systems(Update, spaceship_destroyed.in_set(InGameSet::EntityUpdates))
-            .add_systems(OnEnter(GameState::GameOver), spawn_spaceship);
+            .add_systems(
+                FixedUpdate,
+                (spaceship_destroyed, respawn_spaceship, blink_invulnerable)
+                    .in_set(InGameSet::EntityUpdates),
+            );
     }
 }
 
-fn spawn_spaceship(mut commands: Commands, scene_assets: Res<SceneAssets>) {
-    commands.spawn((
+fn spawn_spaceship(
+    mut commands: Commands,
+    scene_assets: Res<SceneAssets>,
+    config: Res<GameConfig>,
+) {
+    spawn_spaceship_entity(&mut commands, &scene_assets, &config);
+}
+
+/// Spawns a spaceship, immovable from the start under the arcade flight model
+/// since a bundle can't hold a component conditionally.
+fn spawn_spaceship_entity<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    scene_assets: &SceneAssets,
+    config: &GameConfig,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut spaceship = commands.spawn(spaceship_bundle(scene_assets, config));
+    if config.spaceship.flight_model == FlightModel::Arcade {
+        spaceship.insert(Immovable);
+    }
+    spaceship
+}
+
+fn spaceship_bundle(scene_assets: &SceneAssets, config: &GameConfig) -> impl Bundle {
+    let spaceship = &config.spaceship;
+    let shield = &config.shield;
+    (
         MovingObjectBundle {
+            interpolation: TransformInterpolation::default(),
             velocity: Velocity::new(Vec3::ZERO),
             acceleration: Acceleration::new(Vec3::ZERO),
             rotation_velocity: RotationVelocity::new(Vec3::ZERO),
             model: SceneBundle {
                 scene: scene_assets.spaceship.clone(),
-                transform: Transform::from_translation(STARTING_TRANSLATION),
+                transform: Transform::from_translation(spaceship.starting_translation),
                 ..default()
             },
-            collider: Collider::new(SPACESHIP_COLLIDER_RADIUS),
+            collider: Collider::new(
+                spaceship.collider_radius,
+                CollisionLayer::PLAYER,
+                CollisionLayer::ENEMY | CollisionLayer::ENEMY_PROJECTILE | CollisionLayer::PICKUP,
+            ),
         },
         Name::new("Spaceship"),
+        StateScoped(GameState::InGame),
         Spaceship,
-        Health::new(HEALTH),
-        CollisionDemage::new(COLLISION_DEMAGE),
-    ));
+        Wraps,
+        Bounces,
+        Health::new(spaceship.health),
+        CollisionDemage::new(spaceship.collision_demage, DemageType::Kinetic),
+        Mass::new(spaceship.mass),
+        spaceship.resistances,
+        Shield::new(
+            shield.capacity,
+            shield.drain_rate,
+            shield.recharge_rate,
+            shield.cooldown_seconds,
+        ),
+        Weapon::new(config.weapons[0]),
+        WeaponModifiers::default(),
+    )
 }
 
 fn spaceship_movement_controls(
-    mut query: Query<(&Transform, &mut Velocity, &mut RotationVelocity), With<Spaceship>>,
-    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<
+        (
+            &Transform,
+            &mut Velocity,
+            &mut Acceleration,
+            &mut RotationVelocity,
+        ),
+        With<Spaceship>,
+    >,
+    action_state: Res<ActionState>,
+    config: Res<GameConfig>,
+    time: Res<Time>,
 ) {
-    let Ok((transform, mut velocity, mut rotation_component)) = query.get_single_mut() else {
+    let Ok((transform, mut velocity, mut acceleration, mut rotation_component)) =
+        query.get_single_mut()
+    else {
         return;
     };
-    let mut rotation = 0.0;
-    let mut roll = 0.0;
-    let mut movement = 0.0;
-
-    // forward and backward
-    if keyboard_input.pressed(KeyCode::W) {
-        movement = SPACESHIP_SPEED;
-    } else if keyboard_input.pressed(KeyCode::S) {
-        movement = -SPACESHIP_SPEED;
-    }
-    velocity.value = -transform.forward() * movement;
+    let config = &config.spaceship;
+    let thrust = action_state.axis(ActionAxis::Thrust);
+    // turning is proportional for analog sticks
+    let turn = -action_state.axis(ActionAxis::Turn);
+    let roll = action_state.axis(ActionAxis::Roll);
+
+    match config.flight_model {
+        FlightModel::Arcade => {
+            velocity.value = -transform.forward() * thrust * config.speed;
+            acceleration.value = Vec3::ZERO;
+            rotation_component.value.y = turn * config.rotation_speed;
+            rotation_component.value.z = roll * config.roll_speed;
+        }
+        FlightModel::Newtonian => {
+            let newtonian = &config.newtonian;
+            let delta_seconds = time.delta_seconds();
+
+            // forward and backward, slowed down by drag
+            let drag = if action_state.pressed(Action::Brake) {
+                newtonian.brake_drag
+            } else {
+                newtonian.linear_drag
+            };
+            let thrust_acceleration = -transform.forward() * thrust * newtonian.thrust_acceleration;
+            let next_velocity =
+                velocity.value + (thrust_acceleration - velocity.value * drag) * delta_seconds;
+            // only accelerate as far as `MovementPlugin` can integrate
+            // without passing the max speed
+            acceleration.value = (next_velocity.clamp_length_max(newtonian.max_speed)
+                - velocity.value)
+                / delta_seconds;
 
-    // turn left and right
-    if keyboard_input.pressed(KeyCode::A) {
-        rotation = SPACESHIP_ROTATION_SPEED;
-    } else if keyboard_input.pressed(KeyCode::D) {
-        rotation = -SPACESHIP_ROTATION_SPEED;
+            // turn and roll with angular inertia
+            let steer = |current: f32, input: f32, max_speed: f32| {
+                let angular_acceleration =
+                    input * newtonian.angular_acceleration - current * newtonian.angular_drag;
+                (current + angular_acceleration * delta_seconds).clamp(-max_speed, max_speed)
+            };
+            rotation_component.value.y =
+                steer(rotation_component.value.y, turn, config.rotation_speed);
+            rotation_component.value.z = steer(rotation_component.value.z, roll, config.roll_speed);
+        }
     }
-    rotation_component.value.y = rotation;
+}
 
-    // roll
-    if keyboard_input.pressed(KeyCode::E) {
-        roll = SPACESHIP_ROLL_SPEED;
-    } else if keyboard_input.pressed(KeyCode::Q) {
-        roll = -SPACESHIP_ROLL_SPEED;
+/// The arcade flight model sets the velocity outright every step, so it
+/// can't take a push from a collision. Spaceships are spawned with the right
+/// components, so this only follows changes to the flight model.
+fn update_spaceship_immovable(
+    mut commands: Commands,
+    query: Query<(Entity, Has<Immovable>), With<Spaceship>>,
+    config: Res<GameConfig>,
+) {
+    let arcade = config.spaceship.flight_model == FlightModel::Arcade;
+    for (spaceship, immovable) in query.iter() {
+        if arcade && !immovable {
+            commands.entity(spaceship).insert(Immovable);
+        } else if !arcade && immovable {
+            commands.entity(spaceship).remove::<Immovable>();
+        }
     }
-    rotation_component.value.z = roll;
 }
 
 fn spaceship_weapon_controls(
     mut commands: Commands,
-    query: Query<&Transform, With<Spaceship>>,
-    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<(Entity, &Transform, &mut Weapon, &WeaponModifiers), With<Spaceship>>,
+    mut next_weapon_held: Local<bool>,
+    action_state: Res<ActionState>,
     scene_assets: Res<SceneAssets>,
+    config: Res<GameConfig>,
 ) {
-    let Ok(transform) = query.get_single() else {
+    let Ok((spaceship, transform, mut weapon, modifiers)) = query.get_single_mut() else {
         return;
     };
-    if keyboard_input.pressed(KeyCode::Space) {
-        commands.spawn((
-            MovingObjectBundle {
-                velocity: Velocity::new(-transform.forward() * MISSILE_SPEED),
-                acceleration: Acceleration::new(Vec3::ZERO),
-                rotation_velocity: RotationVelocity::new(Vec3::ZERO),
-                collider: Collider::new(MISSILE_COLLIDER_RADIUS),
-                model: SceneBundle {
-                    scene: scene_assets.missiles.clone(),
-                    transform: Transform::from_translation(
-                        transform.translation + -transform.forward() * MISSILE_FORWARD_SPAWN_SCALAR,
-                    ),
-                    ..default()
-                },
-            },
+
+    // switch weapons, checking `pressed` rather than `just_pressed` since a
+    // fixed step may not run in the frame the button went down
+    for (index, &stats) in config.weapons.iter().enumerate() {
+        if action_state.pressed(Action::SelectWeapon(index)) && weapon.stats != stats {
+            weapon.switch_to(stats);
+        }
+    }
+    let next_weapon_pressed = action_state.pressed(Action::NextWeapon);
+    if next_weapon_pressed && !*next_weapon_held {
+        let current = config
+            .weapons
+            .iter()
+            .position(|&stats| stats == weapon.stats);
+        let next = current.map_or(0, |index| (index + 1) % config.weapons.len());
+        weapon.switch_to(config.weapons[next]);
+    }
+    *next_weapon_held = next_weapon_pressed;
+
+    if !action_state.pressed(Action::Fire) || !weapon.try_fire() {
+        return;
+    }
+    let stats = modifiers.apply(weapon.stats);
+    for direction in stats.projectile_directions(-transform.forward()) {
+        let mut projectile = commands.spawn((
+            projectile_bundle(
+                &stats,
+                spaceship,
+                transform.translation,
+                direction,
+                CollisionLayer::PLAYER_PROJECTILE,
+                CollisionLayer::ENEMY,
+                scene_assets.missiles.clone(),
+            ),
             Name::new("SpaceshipMissile"),
             SpaceshipMissile,
-            Health::new(MISSILE_HEALTH),
-            CollisionDemage::new(MISSILE_COLLISION_DEMAGE),
         ));
+        if let Some(homing) = stats.homing {
+            projectile.insert(Homing::new(homing));
+        }
     }
 }
 
 fn spaceship_shield_controls(
-    mut commands: Commands,
-    query: Query<Entity, With<Spaceship>>,
-    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<&mut Shield, With<Spaceship>>,
+    action_state: Res<ActionState>,
 ) {
-    let Ok(spaceship) = query.get_single() else {
+    let Ok(mut shield) = query.get_single_mut() else {
         return;
     };
-    if keyboard_input.pressed(KeyCode::Tab) {
-        commands.entity(spaceship).insert(SpaceshipShield);
+    // raising fails during the cooldown, so only mark the shield changed when
+    // it actually went up or down
+    let was_active = shield.active;
+    shield
+        .bypass_change_detection()
+        .set_active(action_state.pressed(Action::Shield));
+    if shield.active != was_active {
+        shield.set_changed();
     }
 }
 
 fn spaceship_destroyed(
+    mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
+    mut lives: ResMut<Lives>,
+    respawn_timer: Option<Res<RespawnTimer>>,
     query: Query<(), With<Spaceship>>,
+    config: Res<GameConfig>,
 ) {
-    if query.get_single().is_err() {
+    if query.get_single().is_ok() || respawn_timer.is_some() {
+        return;
+    }
+    lives.remaining = lives.remaining.saturating_sub(1);
+    if lives.remaining == 0 {
         next_state.set(GameState::GameOver);
+    } else {
+        commands.insert_resource(RespawnTimer {
+            timer: Timer::from_seconds(config.spaceship.respawn_delay_seconds, TimerMode::Once),
+        });
+    }
+}
+
+fn respawn_spaceship(
+    mut commands: Commands,
+    respawn_timer: Option<ResMut<RespawnTimer>>,
+    time: Res<Time>,
+    scene_assets: Res<SceneAssets>,
+    config: Res<GameConfig>,
+) {
+    let Some(mut respawn_timer) = respawn_timer else {
+        return;
+    };
+    if !respawn_timer.timer.tick(time.delta()).finished() {
+        return;
     }
+    commands.remove_resource::<RespawnTimer>();
+    spawn_spaceship_entity(&mut commands, &scene_assets, &config)
+        .insert(Invulnerable::new(config.spaceship.invulnerability_seconds));
+}
+
+fn blink_invulnerable(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Invulnerable, &mut Visibility)>,
+    time: Res<Time>,
+) {
+    for (entity, mut invulnerable, mut visibility) in query.iter_mut() {
+        if invulnerable.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+            *visibility = Visibility::Inherited;
+            continue;
+        }
+        if invulnerable.blink_timer.tick(time.delta()).just_finished() {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+fn cancel_respawn(mut commands: Commands) {
+    commands.remove_resource::<RespawnTimer>();
+}
+
+fn reset_lives(mut lives: ResMut<Lives>, config: Res<GameConfig>) {
+    lives.remaining = config.spaceship.lives;
 }
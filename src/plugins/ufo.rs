@@ -0,0 +1,202 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use super::{
+    asteroids::{spawn_asteroid, Asteroid},
+    collision_detection::{Collider, CollisionDemage, CollisionLayer},
+    demage::DemageType,
+    homing::Homing,
+    interpolation::TransformInterpolation,
+    movement::{Acceleration, Mass, Velocity},
+    playfield::Wraps,
+    rotation::RotationVelocity,
+    schedule::InGameSet,
+    score::ScoreValue,
+    spaceship::Spaceship,
+    waves::run_wave_director,
+    weapon::{projectile_bundle, Weapon},
+};
+use crate::{
+    bundles::moving_object::MovingObjectBundle,
+    health::Health,
+    resources::{asset_loader::SceneAssets, game_config::GameConfig, rng::GameRng},
+    state::{GameState, StateScoped},
+};
+
+/// An enemy ship that hunts the spaceship and shoots at it.
+#[derive(Component, Debug)]
+pub struct Ufo;
+
+#[derive(Component, Debug)]
+pub struct UfoMissile;
+
+/// Asks for one UFO on a random edge of the playfield.
+#[derive(Event, Debug)]
+pub struct SpawnUfoEvent;
+
+pub struct UfoPlugin;
+
+impl Plugin for UfoPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnUfoEvent>()
+            .add_systems(
+                FixedUpdate,
+                spawn_ufo
+                    .after(run_wave_director)
+                    .after(spawn_asteroid)
+                    .in_set(InGameSet::EntityUpdates),
+            )
+            .add_systems(
+                FixedUpdate,
+                (steer_ufos, ufo_weapon_controls).in_set(InGameSet::UserInput),
+            );
+    }
+}
+
+fn spawn_ufo(
+    mut commands: Commands,
+    mut spawn_ufo_event_reader: EventReader<SpawnUfoEvent>,
+    scene_assets: Res<SceneAssets>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+) {
+    let half_extents = config.playfield.half_extents;
+    let config = &config.ufos;
+
+    for _ in spawn_ufo_event_reader.read() {
+        // somewhere along one of the four edges
+        let along = rng.gen_range(-1.0..1.0);
+        let side = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+        let translation = if rng.gen_bool(0.5) {
+            Vec3::new(side * half_extents.x, 0.0, along * half_extents.y)
+        } else {
+            Vec3::new(along * half_extents.x, 0.0, side * half_extents.y)
+        };
+
+        commands.spawn((
+            MovingObjectBundle {
+                interpolation: TransformInterpolation::default(),
+                velocity: Velocity::new(Vec3::ZERO),
+                acceleration: Acceleration::new(Vec3::ZERO),
+                rotation_velocity: RotationVelocity::new(Vec3::ZERO),
+                model: SceneBundle {
+                    scene: scene_assets.spaceship.clone(),
+                    transform: Transform::from_translation(translation)
+                        .with_scale(Vec3::splat(config.scale)),
+                    ..default()
+                },
+                collider: Collider::new(
+                    config.collider_radius,
+                    CollisionLayer::ENEMY,
+                    CollisionLayer::PLAYER | CollisionLayer::PLAYER_PROJECTILE,
+                ),
+            },
+            Name::new("Ufo"),
+            StateScoped(GameState::InGame),
+            Ufo,
+            Wraps,
+            Weapon::new(config.weapon),
+            ScoreValue::new(config.points),
+            Health::new(config.health),
+            CollisionDemage::new(config.collision_demage, DemageType::Kinetic),
+            Mass::new(config.mass),
+            config.resistances,
+        ));
+    }
+}
+
+type AsteroidFilter = (With<Asteroid>, Without<Ufo>);
+
+/// Closes in on the spaceship until `orbit_radius`, then circles it, while
+/// steering clear of nearby asteroids.
+fn steer_ufos(
+    mut query: Query<(&mut Transform, &Velocity, &mut Acceleration), With<Ufo>>,
+    spaceship_query: Query<&Transform, (With<Spaceship>, Without<Ufo>)>,
+    asteroid_query: Query<(&Transform, &Collider), AsteroidFilter>,
+    config: Res<GameConfig>,
+) {
+    let config = &config.ufos;
+    let spaceship_translation = spaceship_query
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation);
+
+    for (mut transform, velocity, mut acceleration) in query.iter_mut() {
+        let translation = transform.translation;
+        let mut desired = match spaceship_translation {
+            Some(target) => {
+                let offset = (target - translation) * Vec3::new(1.0, 0.0, 1.0);
+                let direction = offset.normalize_or_zero();
+                if offset.length() > config.orbit_radius {
+                    direction
+                } else {
+                    // tangent, nudged back towards the orbit
+                    let tangent = Vec3::new(-direction.z, 0.0, direction.x);
+                    let correction = (offset.length() - config.orbit_radius) / config.orbit_radius;
+                    (tangent + direction * correction).normalize_or_zero()
+                }
+            }
+            // no one to hunt, keep drifting
+            None => velocity.value.normalize_or_zero(),
+        };
+
+        for (asteroid_transform, asteroid_collider) in asteroid_query.iter() {
+            let away = (translation - asteroid_transform.translation) * Vec3::new(1.0, 0.0, 1.0);
+            let gap = away.length() - asteroid_collider.radius - config.collider_radius;
+            if gap < config.avoid_distance {
+                let strength = 1.0 - gap.max(0.0) / config.avoid_distance;
+                desired += away.normalize_or_zero() * strength * 2.0;
+            }
+        }
+
+        let desired_velocity = desired.normalize_or_zero() * config.speed;
+        acceleration.value =
+            (desired_velocity - velocity.value).clamp_length_max(config.max_acceleration);
+
+        if let Some(target) = spaceship_translation {
+            let direction = (target - translation) * Vec3::new(1.0, 0.0, 1.0);
+            if direction != Vec3::ZERO {
+                // the model's nose points along its local +Z
+                transform.look_to(-direction, Vec3::Y);
+            }
+        }
+    }
+}
+
+fn ufo_weapon_controls(
+    mut commands: Commands,
+    mut query: Query<(Entity, &Transform, &mut Weapon), With<Ufo>>,
+    spaceship_query: Query<&Transform, With<Spaceship>>,
+    scene_assets: Res<SceneAssets>,
+    config: Res<GameConfig>,
+) {
+    let Ok(spaceship_transform) = spaceship_query.get_single() else {
+        return;
+    };
+
+    for (ufo, transform, mut weapon) in query.iter_mut() {
+        let offset = spaceship_transform.translation - transform.translation;
+        if offset.length() > config.ufos.fire_range || !weapon.try_fire() {
+            continue;
+        }
+        let stats = weapon.stats;
+        for direction in stats.projectile_directions(offset.normalize_or_zero()) {
+            let mut projectile = commands.spawn((
+                projectile_bundle(
+                    &stats,
+                    ufo,
+                    transform.translation,
+                    direction,
+                    CollisionLayer::ENEMY_PROJECTILE,
+                    CollisionLayer::PLAYER,
+                    scene_assets.missiles.clone(),
+                ),
+                Name::new("UfoMissile"),
+                UfoMissile,
+            ));
+            if let Some(homing) = stats.homing {
+                projectile.insert(Homing::new(homing));
+            }
+        }
+    }
+}
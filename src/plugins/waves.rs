@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    asteroids::{Asteroid, SpawnAsteroidEvent},
+    schedule::InGameSet,
+    ufo::{SpawnUfoEvent, Ufo},
+};
+use crate::{resources::game_config::GameConfig, state::NewRun};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WaveDefinition {
+    pub asteroid_count: u32,
+    #[serde(default)]
+    pub ufo_count: u32,
+    pub spawn_interval_seconds: f32,
+    pub velocity_scalar: f32,
+    pub acceleration_scalar: f32,
+}
+
+/// How waves past the end of the table grow, applied once per extra wave.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WaveEscalation {
+    pub extra_asteroids: u32,
+    #[serde(default)]
+    pub extra_ufos: u32,
+    pub speed_multiplier: f32,
+    pub spawn_interval_multiplier: f32,
+    pub min_spawn_interval_seconds: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WaveTable {
+    pub waves: Vec<WaveDefinition>,
+    pub escalation: WaveEscalation,
+    pub intermission_seconds: f32,
+}
+
+impl WaveTable {
+    /// The definition of wave `number`, counting from 1.
+    pub fn wave(&self, number: u32) -> WaveDefinition {
+        let index = number.saturating_sub(1) as usize;
+        if let Some(definition) = self.waves.get(index) {
+            return definition.clone();
+        }
+
+        // validation guarantees at least one wave
+        let last = &self.waves[self.waves.len() - 1];
+        let extra_waves = (index + 1 - self.waves.len()) as u32;
+        let speed_multiplier = self.escalation.speed_multiplier.powi(extra_waves as i32);
+        WaveDefinition {
+            asteroid_count: last.asteroid_count + self.escalation.extra_asteroids * extra_waves,
+            ufo_count: last.ufo_count + self.escalation.extra_ufos * extra_waves,
+            spawn_interval_seconds: (last.spawn_interval_seconds
+                * self
+                    .escalation
+                    .spawn_interval_multiplier
+                    .powi(extra_waves as i32))
+            .max(self.escalation.min_spawn_interval_seconds),
+            velocity_scalar: last.velocity_scalar * speed_multiplier,
+            acceleration_scalar: last.acceleration_scalar * speed_multiplier,
+        }
+    }
+}
+
+#[derive(Event, Debug)]
+pub struct WaveClearedEvent {
+    pub wave: u32,
+}
+
+#[derive(Debug)]
+enum WavePhase {
+    Intermission {
+        timer: Timer,
+    },
+    Spawning {
+        definition: WaveDefinition,
+        remaining_asteroids: u32,
+        remaining_ufos: u32,
+        timer: Timer,
+    },
+    /// Everything has been spawned, waiting for the last asteroid or UFO to
+    /// go.
+    Clearing,
+}
+
+#[derive(Resource, Debug)]
+pub struct WaveDirector {
+    /// The current wave number, 0 before the first wave starts.
+    pub wave: u32,
+    phase: WavePhase,
+}
+
+impl WaveDirector {
+    fn new(intermission_seconds: f32) -> Self {
+        Self {
+            wave: 0,
+            phase: WavePhase::Intermission {
+                timer: Timer::from_seconds(intermission_seconds, TimerMode::Once),
+            },
+        }
+    }
+}
+
+pub struct WavesPlugin;
+
+impl Plugin for WavesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(WaveDirector::new(0.0))
+            .add_event::<WaveClearedEvent>()
+            .add_systems(
+                FixedUpdate,
+                run_wave_director.in_set(InGameSet::EntityUpdates),
+            )
+            .add_systems(NewRun, reset_wave_director);
+    }
+}
+
+/// Anything that has to be destroyed before the wave counts as cleared.
+type HostileFilter = Or<(With<Asteroid>, With<Ufo>)>;
+
+/// Sends this step's spawn and wave-cleared events. Readers order themselves
+/// after it so the events are handled in the same fixed step.
+pub fn run_wave_director(
+    mut director: ResMut<WaveDirector>,
+    mut spawn_asteroid_event_writer: EventWriter<SpawnAsteroidEvent>,
+    mut spawn_ufo_event_writer: EventWriter<SpawnUfoEvent>,
+    mut wave_cleared_event_writer: EventWriter<WaveClearedEvent>,
+    config: Res<GameConfig>,
+    hostile_query: Query<(), HostileFilter>,
+    time: Res<Time>,
+) {
+    let wave_table = &config.waves;
+    let wave = director.wave;
+    // the HUD only cares about the wave number, not about every tick
+    let next_phase = match &mut director.bypass_change_detection().phase {
+        WavePhase::Intermission { timer } => {
+            if !timer.tick(time.delta()).finished() {
+                return;
+            }
+            let definition = wave_table.wave(wave + 1);
+            WavePhase::Spawning {
+                remaining_asteroids: definition.asteroid_count,
+                remaining_ufos: definition.ufo_count,
+                timer: Timer::from_seconds(definition.spawn_interval_seconds, TimerMode::Repeating),
+                definition,
+            }
+        }
+        WavePhase::Spawning {
+            definition,
+            remaining_asteroids,
+            remaining_ufos,
+            timer,
+        } => {
+            if !timer.tick(time.delta()).just_finished() {
+                return;
+            }
+            // Only start watching for the clear one interval after the last
+            // spawn, so that spawn is guaranteed to be in the world.
+            if *remaining_asteroids > 0 {
+                *remaining_asteroids -= 1;
+                spawn_asteroid_event_writer.send(SpawnAsteroidEvent::new(
+                    definition.velocity_scalar,
+                    definition.acceleration_scalar,
+                ));
+                return;
+            } else if *remaining_ufos > 0 {
+                *remaining_ufos -= 1;
+                spawn_ufo_event_writer.send(SpawnUfoEvent);
+                return;
+            } else {
+                WavePhase::Clearing
+            }
+        }
+        WavePhase::Clearing => {
+            if !hostile_query.is_empty() {
+                return;
+            }
+            info!("Wave {wave} cleared");
+            wave_cleared_event_writer.send(WaveClearedEvent { wave });
+            WavePhase::Intermission {
+                timer: Timer::from_seconds(wave_table.intermission_seconds, TimerMode::Once),
+            }
+        }
+    };
+    if matches!(next_phase, WavePhase::Spawning { .. }) {
+        director.wave += 1;
+        info!("Wave {} started", director.wave);
+    }
+    director.bypass_change_detection().phase = next_phase;
+}
+
+fn reset_wave_director(mut director: ResMut<WaveDirector>, config: Res<GameConfig>) {
+    *director = WaveDirector::new(config.waves.intermission_seconds);
+}
@@ -0,0 +1,253 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    collision_detection::{Collider, CollisionDemage, CollisionLayer},
+    demage::DemageType,
+    despawn::Lifetime,
+    homing::HomingStats,
+    interpolation::TransformInterpolation,
+    movement::{Acceleration, Mass, Velocity},
+    rotation::RotationVelocity,
+    schedule::InGameSet,
+};
+use crate::{
+    bundles::moving_object::MovingObjectBundle,
+    health::Health,
+    state::{GameState, StateScoped},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct WeaponStats {
+    pub cooldown_seconds: f32,
+    pub projectile_speed: f32,
+    /// Angle in radians that the projectiles of one burst are fanned across.
+    pub spread: f32,
+    pub burst_count: u32,
+    pub projectile_lifetime_seconds: f32,
+    /// Distance the projectiles fly before disappearing, if they don't run
+    /// out of lifetime first.
+    #[serde(default)]
+    pub projectile_range: Option<f32>,
+    pub forward_spawn_scalar: f32,
+    pub projectile_collider_radius: f32,
+    pub projectile_health: f32,
+    pub projectile_collision_demage: f32,
+    #[serde(default)]
+    pub projectile_demage_type: DemageType,
+    /// Knocks targets back when above zero.
+    #[serde(default)]
+    pub projectile_mass: f32,
+    /// Makes the projectiles steer towards targets.
+    #[serde(default)]
+    pub homing: Option<HomingStats>,
+}
+
+impl WeaponStats {
+    /// Directions of every projectile in one burst, fanned evenly around
+    /// `forward` in the horizontal plane.
+    pub fn projectile_directions(&self, forward: Vec3) -> impl Iterator<Item = Vec3> {
+        let Self {
+            spread,
+            burst_count,
+            ..
+        } = *self;
+        (0..burst_count).map(move |i| {
+            let angle = if burst_count > 1 {
+                -spread / 2.0 + spread * i as f32 / (burst_count - 1) as f32
+            } else {
+                0.0
+            };
+            Quat::from_rotation_y(angle) * forward
+        })
+    }
+}
+
+/// Fires faster for a while.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RapidFire {
+    pub duration_seconds: f32,
+    /// How many times faster the cooldown runs out.
+    pub fire_rate_multiplier: f32,
+}
+
+/// Adds projectiles to every burst for a while.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct SpreadShot {
+    pub duration_seconds: f32,
+    pub extra_projectiles: u32,
+    /// The burst is fanned across at least this angle in radians.
+    pub spread: f32,
+}
+
+/// Marks an entity fired by a weapon, so damage it deals is credited to
+/// the shooter.
+#[derive(Component, Debug)]
+pub struct Projectile {
+    pub owner: Entity,
+}
+
+impl Projectile {
+    pub fn new(owner: Entity) -> Self {
+        Self { owner }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Weapon {
+    pub stats: WeaponStats,
+    cooldown: Timer,
+}
+
+impl Weapon {
+    pub fn new(stats: WeaponStats) -> Self {
+        let mut cooldown = Timer::from_seconds(stats.cooldown_seconds, TimerMode::Once);
+        // A fresh weapon can fire straight away.
+        cooldown.tick(cooldown.duration());
+        Self { stats, cooldown }
+    }
+
+    /// Starts the cooldown and returns `true` if the weapon was ready to fire.
+    pub fn try_fire(&mut self) -> bool {
+        if !self.cooldown.finished() {
+            return false;
+        }
+        self.cooldown.reset();
+        true
+    }
+
+    /// Swaps in `stats`, carrying over whatever is left of the cooldown so
+    /// switching back and forth can't skip it.
+    pub fn switch_to(&mut self, stats: WeaponStats) {
+        let remaining = self.cooldown.remaining();
+        self.stats = stats;
+        self.cooldown
+            .set_duration(Duration::from_secs_f32(stats.cooldown_seconds));
+        self.cooldown.reset();
+        self.cooldown
+            .tick(self.cooldown.duration().saturating_sub(remaining));
+    }
+}
+
+/// Temporary boosts from pickups, applied on top of whichever weapon is
+/// equipped so they survive switching weapons.
+#[derive(Component, Debug, Default)]
+pub struct WeaponModifiers {
+    rapid_fire: Option<(RapidFire, Timer)>,
+    spread_shot: Option<(SpreadShot, Timer)>,
+}
+
+impl WeaponModifiers {
+    /// Starts rapid fire, or restarts it if it is already running.
+    pub fn add_rapid_fire(&mut self, rapid_fire: RapidFire) {
+        let timer = Timer::from_seconds(rapid_fire.duration_seconds, TimerMode::Once);
+        self.rapid_fire = Some((rapid_fire, timer));
+    }
+
+    /// Starts spread shot, or restarts it if it is already running.
+    pub fn add_spread_shot(&mut self, spread_shot: SpreadShot) {
+        let timer = Timer::from_seconds(spread_shot.duration_seconds, TimerMode::Once);
+        self.spread_shot = Some((spread_shot, timer));
+    }
+
+    /// The burst `stats` fires while the boosts are running. Rapid fire is
+    /// applied to the cooldown separately.
+    pub fn apply(&self, stats: WeaponStats) -> WeaponStats {
+        let mut stats = stats;
+        if let Some((spread_shot, _)) = &self.spread_shot {
+            stats.burst_count += spread_shot.extra_projectiles;
+            stats.spread = stats.spread.max(spread_shot.spread);
+        }
+        stats
+    }
+
+    fn fire_rate_multiplier(&self) -> f32 {
+        self.rapid_fire
+            .as_ref()
+            .map_or(1.0, |(rapid_fire, _)| rapid_fire.fire_rate_multiplier)
+    }
+
+    fn tick(&mut self, delta: Duration) {
+        if let Some((_, timer)) = &mut self.rapid_fire {
+            if timer.tick(delta).finished() {
+                self.rapid_fire = None;
+            }
+        }
+        if let Some((_, timer)) = &mut self.spread_shot {
+            if timer.tick(delta).finished() {
+                self.spread_shot = None;
+            }
+        }
+    }
+}
+
+/// One projectile of a burst, fired by `owner` from `origin` along
+/// `direction`, colliding as `layer` with whatever is in `mask`.
+pub fn projectile_bundle(
+    stats: &WeaponStats,
+    owner: Entity,
+    origin: Vec3,
+    direction: Vec3,
+    layer: CollisionLayer,
+    mask: CollisionLayer,
+    scene: Handle<Scene>,
+) -> impl Bundle {
+    (
+        MovingObjectBundle {
+            interpolation: TransformInterpolation::default(),
+            velocity: Velocity::new(direction * stats.projectile_speed),
+            acceleration: Acceleration::new(Vec3::ZERO),
+            rotation_velocity: RotationVelocity::new(Vec3::ZERO),
+            collider: Collider::new(stats.projectile_collider_radius, layer, mask),
+            model: SceneBundle {
+                scene,
+                transform: Transform::from_translation(
+                    origin + direction * stats.forward_spawn_scalar,
+                ),
+                ..default()
+            },
+        },
+        StateScoped(GameState::InGame),
+        Projectile::new(owner),
+        Health::new(stats.projectile_health),
+        CollisionDemage::new(
+            stats.projectile_collision_demage,
+            stats.projectile_demage_type,
+        ),
+        Mass::new(stats.projectile_mass),
+        Lifetime::new(
+            Some(stats.projectile_lifetime_seconds),
+            stats.projectile_range,
+        ),
+    )
+}
+
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            tick_weapon_cooldowns.in_set(InGameSet::EntityUpdates),
+        );
+    }
+}
+
+fn tick_weapon_cooldowns(
+    mut query: Query<(&mut Weapon, Option<&mut WeaponModifiers>)>,
+    time: Res<Time>,
+) {
+    for (mut weapon, modifiers) in &mut query {
+        let delta = match modifiers {
+            Some(mut modifiers) => {
+                let delta = time.delta().mul_f32(modifiers.fire_rate_multiplier());
+                modifiers.tick(time.delta());
+                delta
+            }
+            None => time.delta(),
+        };
+        weapon.cooldown.tick(delta);
+    }
+}
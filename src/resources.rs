@@ -1 +1,3 @@
 pub mod asset_loader;
+pub mod game_config;
+pub mod rng;
@@ -0,0 +1,709 @@
+use std::{
+    f32::consts::PI,
+    ops::{Range, RangeInclusive},
+};
+
+use bevy::{
+    app::AppExit,
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, LoadState},
+    prelude::*,
+    reflect::TypePath,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    plugins::{
+        asteroids::AsteroidSize,
+        camera::{ASPECT_RATIO, FOV},
+        demage::Resistances,
+        pickups::PickupKind,
+        waves::WaveTable,
+        weapon::{RapidFire, SpreadShot, WeaponStats},
+    },
+    state::GameState,
+};
+
+const GAME_CONFIG_PATH: &str = "game.config.ron";
+
+/// Gameplay tuning, loaded from `assets/game.config.ron` and reloaded
+/// whenever that file changes. The current values are also kept as a
+/// resource so systems can read them with `Res<GameConfig>`.
+#[derive(Asset, TypePath, Resource, Debug, Clone, Deserialize)]
+pub struct GameConfig {
+    pub spaceship: SpaceshipConfig,
+    pub shield: ShieldConfig,
+    /// Selected with the number keys, in order. The first is the default.
+    pub weapons: Vec<WeaponStats>,
+    pub asteroids: AsteroidConfig,
+    pub ufos: UfoConfig,
+    pub pickups: PickupConfig,
+    pub waves: WaveTable,
+    pub demage: DemageConfig,
+    pub physics: PhysicsConfig,
+    pub score: ScoreConfig,
+    pub game_over: GameOverConfig,
+    pub playfield: PlayfieldConfig,
+    pub despawn: DespawnConfig,
+    pub camera: CameraConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpaceshipConfig {
+    pub starting_translation: Vec3,
+    pub speed: f32,
+    pub rotation_speed: f32,
+    pub roll_speed: f32,
+    pub collider_radius: f32,
+    pub health: f32,
+    pub collision_demage: f32,
+    pub mass: f32,
+    #[serde(default)]
+    pub resistances: Resistances,
+    pub lives: u32,
+    pub respawn_delay_seconds: f32,
+    pub invulnerability_seconds: f32,
+    pub flight_model: FlightModel,
+    pub newtonian: NewtonianFlightConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum FlightModel {
+    /// The ship moves at `speed` while thrusting and stops dead otherwise.
+    Arcade,
+    /// Thrust accelerates the ship, which keeps drifting until drag or the
+    /// brake slow it down.
+    Newtonian,
+}
+
+/// Drags are the fraction of speed lost per second.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewtonianFlightConfig {
+    pub thrust_acceleration: f32,
+    pub max_speed: f32,
+    pub linear_drag: f32,
+    /// Replaces `linear_drag` while braking.
+    pub brake_drag: f32,
+    /// Turning and rolling are capped at `rotation_speed` and `roll_speed`.
+    pub angular_acceleration: f32,
+    pub angular_drag: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ShieldConfig {
+    pub capacity: f32,
+    pub drain_rate: f32,
+    pub recharge_rate: f32,
+    pub cooldown_seconds: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AsteroidConfig {
+    pub spawn_range_x: Range<f32>,
+    pub spawn_range_z: Range<f32>,
+    pub max_rotation_speed: f32,
+    pub collider_radius: f32,
+    pub health: f32,
+    pub collision_demage: f32,
+    /// Mass of a large asteroid. Smaller ones scale down with their volume.
+    pub mass: f32,
+    #[serde(default)]
+    pub resistances: Resistances,
+    pub fragment_count: RangeInclusive<u32>,
+    pub fragment_velocity_scalar: f32,
+    pub large: AsteroidSizeConfig,
+    pub medium: AsteroidSizeConfig,
+    pub small: AsteroidSizeConfig,
+}
+
+impl AsteroidConfig {
+    pub fn size(&self, size: AsteroidSize) -> &AsteroidSizeConfig {
+        match size {
+            AsteroidSize::Large => &self.large,
+            AsteroidSize::Medium => &self.medium,
+            AsteroidSize::Small => &self.small,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UfoConfig {
+    pub speed: f32,
+    /// How hard the UFO can steer towards where it wants to go.
+    pub max_acceleration: f32,
+    /// Distance from the spaceship at which the UFO stops closing in and
+    /// circles instead.
+    pub orbit_radius: f32,
+    /// Asteroids closer than this, edge to edge, push the UFO away.
+    pub avoid_distance: f32,
+    /// Only fires at a spaceship within this distance.
+    pub fire_range: f32,
+    pub scale: f32,
+    pub collider_radius: f32,
+    pub health: f32,
+    pub collision_demage: f32,
+    pub mass: f32,
+    #[serde(default)]
+    pub resistances: Resistances,
+    pub points: u32,
+    pub weapon: WeaponStats,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AsteroidSizeConfig {
+    /// Scale relative to the base asteroid, applied to the model, collider,
+    /// health and collision demage.
+    pub scale: f32,
+    pub points: u32,
+    #[serde(default)]
+    pub drops: DropTable,
+}
+
+/// What, if anything, an asteroid leaves behind when destroyed.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DropTable {
+    /// Probability from 0 to 1 that anything drops at all.
+    pub chance: f32,
+    /// Relative weights of each kind, picked from once something drops.
+    pub weights: Vec<(PickupKind, f32)>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PickupConfig {
+    pub lifetime_seconds: f32,
+    pub drift_speed: f32,
+    pub max_rotation_speed: f32,
+    pub scale: f32,
+    pub collider_radius: f32,
+    /// Health restored by a repair pickup, up to `spaceship.health`.
+    pub repair_amount: f32,
+    /// Shield energy restored by a shield pickup, up to `shield.capacity`.
+    pub shield_recharge_amount: f32,
+    /// Extra life pickups don't add lives past this many.
+    pub max_lives: u32,
+    pub rapid_fire: RapidFire,
+    pub spread_shot: SpreadShot,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DemageConfig {
+    /// How long one entity is immune to further demage from the same source
+    /// after being hit by it.
+    pub hit_cooldown_seconds: f32,
+    /// Scales the knockback from collisions. 0 turns it off.
+    pub knockback_scalar: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PhysicsConfig {
+    /// Asteroids and the spaceship bounce off each other instead of
+    /// overlapping.
+    pub elastic_collisions: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScoreConfig {
+    pub combo_window_seconds: f32,
+    pub max_combo_multiplier: u32,
+    pub wave_clear_bonus_points: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GameOverConfig {
+    pub timeout_seconds: f32,
+}
+
+/// A rectangle on the xz plane centered on the origin.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlayfieldConfig {
+    /// Wrap around the edges instead of despawning anything that strays
+    /// `despawn.distance` from the origin.
+    pub wrap: bool,
+    /// Half the width (x) and height (z) of the playfield.
+    pub half_extents: Vec2,
+}
+
+impl PlayfieldConfig {
+    pub fn contains(&self, translation: Vec3) -> bool {
+        translation.x.abs() <= self.half_extents.x && translation.z.abs() <= self.half_extents.y
+    }
+
+    /// Brings a translation that left the playfield back in from the
+    /// opposite edge.
+    pub fn wrap_translation(&self, translation: Vec3) -> Vec3 {
+        let wrap = |value: f32, half_extent: f32| {
+            (value + half_extent).rem_euclid(2.0 * half_extent) - half_extent
+        };
+        Vec3::new(
+            wrap(translation.x, self.half_extents.x),
+            translation.y,
+            wrap(translation.z, self.half_extents.y),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DespawnConfig {
+    pub distance: f32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CameraConfig {
+    pub distance: f32,
+}
+
+impl CameraConfig {
+    /// Half the width (x) and height (z) of what the camera sees on the
+    /// xz plane.
+    pub fn visible_half_extents(&self) -> Vec2 {
+        let half_height = self.distance * (FOV / 2.0).tan();
+        Vec2::new(half_height * ASPECT_RATIO, half_height)
+    }
+}
+
+impl GameConfig {
+    fn validate(&self) -> Result<(), GameConfigError> {
+        let mut problems = Vec::new();
+        let mut check = |ok: bool, problem: &str| {
+            if !ok {
+                problems.push(problem.to_string());
+            }
+        };
+
+        let spaceship = &self.spaceship;
+        check(
+            spaceship.speed >= 0.0,
+            "spaceship.speed must not be negative",
+        );
+        check(
+            spaceship.rotation_speed >= 0.0,
+            "spaceship.rotation_speed must not be negative",
+        );
+        check(
+            spaceship.roll_speed >= 0.0,
+            "spaceship.roll_speed must not be negative",
+        );
+        check(
+            spaceship.collider_radius > 0.0,
+            "spaceship.collider_radius must be positive",
+        );
+        check(spaceship.health > 0.0, "spaceship.health must be positive");
+        check(spaceship.mass > 0.0, "spaceship.mass must be positive");
+        check(
+            valid_resistances(&spaceship.resistances),
+            "spaceship.resistances must be at most 1",
+        );
+        check(spaceship.lives > 0, "spaceship.lives must be at least 1");
+        check(
+            spaceship.respawn_delay_seconds >= 0.0,
+            "spaceship.respawn_delay_seconds must not be negative",
+        );
+        check(
+            spaceship.invulnerability_seconds >= 0.0,
+            "spaceship.invulnerability_seconds must not be negative",
+        );
+        let newtonian = &spaceship.newtonian;
+        check(
+            newtonian.thrust_acceleration >= 0.0,
+            "spaceship.newtonian.thrust_acceleration must not be negative",
+        );
+        check(
+            newtonian.max_speed >= 0.0,
+            "spaceship.newtonian.max_speed must not be negative",
+        );
+        check(
+            newtonian.linear_drag >= 0.0,
+            "spaceship.newtonian.linear_drag must not be negative",
+        );
+        check(
+            newtonian.brake_drag >= 0.0,
+            "spaceship.newtonian.brake_drag must not be negative",
+        );
+        check(
+            newtonian.angular_acceleration >= 0.0,
+            "spaceship.newtonian.angular_acceleration must not be negative",
+        );
+        check(
+            newtonian.angular_drag >= 0.0,
+            "spaceship.newtonian.angular_drag must not be negative",
+        );
+
+        let shield = &self.shield;
+        check(shield.capacity > 0.0, "shield.capacity must be positive");
+        check(
+            shield.drain_rate >= 0.0,
+            "shield.drain_rate must not be negative",
+        );
+        check(
+            shield.recharge_rate >= 0.0,
+            "shield.recharge_rate must not be negative",
+        );
+        check(
+            shield.cooldown_seconds >= 0.0,
+            "shield.cooldown_seconds must not be negative",
+        );
+
+        check(!self.weapons.is_empty(), "weapons must not be empty");
+        for (index, weapon) in self.weapons.iter().enumerate() {
+            for problem in validate_weapon(&format!("weapons[{index}]"), weapon) {
+                check(false, &problem);
+            }
+        }
+
+        let asteroids = &self.asteroids;
+        check(
+            !asteroids.spawn_range_x.is_empty(),
+            "asteroids.spawn_range_x must not be empty",
+        );
+        check(
+            !asteroids.spawn_range_z.is_empty(),
+            "asteroids.spawn_range_z must not be empty",
+        );
+        check(
+            asteroids.max_rotation_speed > 0.0,
+            "asteroids.max_rotation_speed must be positive",
+        );
+        check(
+            asteroids.collider_radius > 0.0,
+            "asteroids.collider_radius must be positive",
+        );
+        check(asteroids.health > 0.0, "asteroids.health must be positive");
+        check(asteroids.mass > 0.0, "asteroids.mass must be positive");
+        check(
+            valid_resistances(&asteroids.resistances),
+            "asteroids.resistances must be at most 1",
+        );
+        check(
+            !asteroids.fragment_count.is_empty(),
+            "asteroids.fragment_count must not be empty",
+        );
+        for size in [&asteroids.large, &asteroids.medium, &asteroids.small] {
+            check(size.scale > 0.0, "asteroids: size scale must be positive");
+            check(
+                (0.0..=1.0).contains(&size.drops.chance),
+                "asteroids: drops.chance must be between 0 and 1",
+            );
+            check(
+                size.drops.weights.iter().all(|&(_, weight)| weight >= 0.0),
+                "asteroids: drops.weights must not be negative",
+            );
+            check(
+                size.drops.chance == 0.0
+                    || size.drops.weights.iter().any(|&(_, weight)| weight > 0.0),
+                "asteroids: drops.weights needs a positive weight when drops.chance is set",
+            );
+        }
+
+        let ufos = &self.ufos;
+        check(ufos.speed >= 0.0, "ufos.speed must not be negative");
+        check(
+            ufos.max_acceleration > 0.0,
+            "ufos.max_acceleration must be positive",
+        );
+        check(
+            ufos.orbit_radius > 0.0,
+            "ufos.orbit_radius must be positive",
+        );
+        check(
+            ufos.avoid_distance > 0.0,
+            "ufos.avoid_distance must be positive",
+        );
+        check(ufos.scale > 0.0, "ufos.scale must be positive");
+        check(
+            ufos.collider_radius > 0.0,
+            "ufos.collider_radius must be positive",
+        );
+        check(ufos.health > 0.0, "ufos.health must be positive");
+        check(ufos.mass > 0.0, "ufos.mass must be positive");
+        check(
+            valid_resistances(&ufos.resistances),
+            "ufos.resistances must be at most 1",
+        );
+        for problem in validate_weapon("ufos.weapon", &ufos.weapon) {
+            check(false, &problem);
+        }
+
+        let pickups = &self.pickups;
+        check(
+            pickups.lifetime_seconds > 0.0,
+            "pickups.lifetime_seconds must be positive",
+        );
+        check(
+            pickups.drift_speed >= 0.0,
+            "pickups.drift_speed must not be negative",
+        );
+        check(
+            pickups.max_rotation_speed > 0.0,
+            "pickups.max_rotation_speed must be positive",
+        );
+        check(pickups.scale > 0.0, "pickups.scale must be positive");
+        check(
+            pickups.collider_radius > 0.0,
+            "pickups.collider_radius must be positive",
+        );
+        check(
+            pickups.max_lives >= self.spaceship.lives,
+            "pickups.max_lives must be at least spaceship.lives",
+        );
+        check(
+            pickups.rapid_fire.duration_seconds > 0.0,
+            "pickups.rapid_fire.duration_seconds must be positive",
+        );
+        check(
+            pickups.rapid_fire.fire_rate_multiplier > 0.0,
+            "pickups.rapid_fire.fire_rate_multiplier must be positive",
+        );
+        check(
+            pickups.spread_shot.duration_seconds > 0.0,
+            "pickups.spread_shot.duration_seconds must be positive",
+        );
+
+        let waves = &self.waves;
+        check(!waves.waves.is_empty(), "waves.waves must not be empty");
+        for wave in &waves.waves {
+            check(
+                wave.spawn_interval_seconds > 0.0,
+                "waves: spawn_interval_seconds must be positive",
+            );
+        }
+        check(
+            waves.escalation.min_spawn_interval_seconds > 0.0,
+            "waves.escalation.min_spawn_interval_seconds must be positive",
+        );
+        check(
+            waves.intermission_seconds >= 0.0,
+            "waves.intermission_seconds must not be negative",
+        );
+
+        check(
+            self.demage.hit_cooldown_seconds >= 0.0,
+            "demage.hit_cooldown_seconds must not be negative",
+        );
+        check(
+            self.demage.knockback_scalar >= 0.0,
+            "demage.knockback_scalar must not be negative",
+        );
+        check(
+            self.score.combo_window_seconds > 0.0,
+            "score.combo_window_seconds must be positive",
+        );
+        check(
+            self.score.max_combo_multiplier > 0,
+            "score.max_combo_multiplier must be at least 1",
+        );
+        check(
+            self.game_over.timeout_seconds >= 0.0,
+            "game_over.timeout_seconds must not be negative",
+        );
+        check(
+            self.playfield.half_extents.x > 0.0 && self.playfield.half_extents.y > 0.0,
+            "playfield.half_extents must be positive",
+        );
+        check(
+            self.despawn.distance > 0.0,
+            "despawn.distance must be positive",
+        );
+        check(
+            self.camera.distance > 0.0,
+            "camera.distance must be positive",
+        );
+        // things should wrap right at the edges of the screen
+        let visible = self.camera.visible_half_extents();
+        check(
+            (self.playfield.half_extents / visible - Vec2::ONE)
+                .abs()
+                .max_element()
+                <= 0.05,
+            "playfield.half_extents must be within 5% of what the camera sees at camera.distance",
+        );
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(GameConfigError::Invalid(problems.join("; ")))
+        }
+    }
+}
+
+/// Everything wrong with one weapon, each problem starting with `prefix`.
+fn validate_weapon(prefix: &str, weapon: &WeaponStats) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut check = |ok: bool, problem: &str| {
+        if !ok {
+            problems.push(format!("{prefix}.{problem}"));
+        }
+    };
+
+    check(
+        weapon.cooldown_seconds >= 0.0,
+        "cooldown_seconds must not be negative",
+    );
+    check(weapon.burst_count > 0, "burst_count must be at least 1");
+    check(
+        weapon.projectile_lifetime_seconds > 0.0,
+        "projectile_lifetime_seconds must be positive",
+    );
+    check(
+        weapon.projectile_collider_radius > 0.0,
+        "projectile_collider_radius must be positive",
+    );
+    check(
+        weapon.projectile_health > 0.0,
+        "projectile_health must be positive",
+    );
+    check(
+        weapon.projectile_mass >= 0.0,
+        "projectile_mass must not be negative",
+    );
+    check(
+        weapon.projectile_range.is_none_or(|range| range > 0.0),
+        "projectile_range must be positive",
+    );
+    if let Some(homing) = &weapon.homing {
+        check(
+            (0.0..=PI).contains(&homing.cone_angle),
+            "homing.cone_angle must be between 0 and pi",
+        );
+        check(homing.range > 0.0, "homing.range must be positive");
+        check(
+            homing.turn_rate >= 0.0,
+            "homing.turn_rate must not be negative",
+        );
+    }
+    problems
+}
+
+/// Resisting more than all of the demage would heal instead.
+fn valid_resistances(resistances: &Resistances) -> bool {
+    [
+        resistances.kinetic,
+        resistances.explosive,
+        resistances.energy,
+    ]
+    .iter()
+    .all(|&resistance| resistance <= 1.0)
+}
+
+#[derive(Debug, Error)]
+pub enum GameConfigError {
+    #[error("could not read game config: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("malformed game config: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("invalid game config: {0}")]
+    Invalid(String),
+}
+
+#[derive(Default)]
+pub struct GameConfigLoader;
+
+impl AssetLoader for GameConfigLoader {
+    type Asset = GameConfig;
+    type Settings = ();
+    type Error = GameConfigError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<GameConfig, GameConfigError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let config: GameConfig = ron::de::from_bytes(&bytes)?;
+            config.validate()?;
+            Ok(config)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["config.ron"]
+    }
+}
+
+#[derive(Resource, Debug)]
+struct GameConfigHandle {
+    handle: Handle<GameConfig>,
+}
+
+pub struct GameConfigPlugin;
+
+impl Plugin for GameConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<GameConfig>()
+            .init_asset_loader::<GameConfigLoader>()
+            .add_systems(Startup, load_game_config)
+            .add_systems(
+                Update,
+                (
+                    apply_game_config_changes,
+                    finish_loading.run_if(in_state(GameState::Loading)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+fn load_game_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(GameConfigHandle {
+        handle: asset_server.load(GAME_CONFIG_PATH),
+    });
+}
+
+fn apply_game_config_changes(
+    mut commands: Commands,
+    mut asset_event_reader: EventReader<AssetEvent<GameConfig>>,
+    game_config_handle: Res<GameConfigHandle>,
+    game_configs: Res<Assets<GameConfig>>,
+) {
+    for event in asset_event_reader.read() {
+        let handle = &game_config_handle.handle;
+        if !event.is_loaded_with_dependencies(handle) && !event.is_modified(handle) {
+            continue;
+        }
+        if let Some(game_config) = game_configs.get(handle) {
+            info!("Applied game config from {GAME_CONFIG_PATH}");
+            commands.insert_resource(game_config.clone());
+        }
+    }
+}
+
+/// Quits if the config can't be read or is invalid at startup, since there is
+/// nothing to play with. The asset server logs why.
+fn finish_loading(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut app_exit_event_writer: EventWriter<AppExit>,
+    game_config: Option<Res<GameConfig>>,
+    game_config_handle: Res<GameConfigHandle>,
+    asset_server: Res<AssetServer>,
+) {
+    if game_config.is_some() {
+        next_state.set(GameState::MainMenu);
+    } else if asset_server.get_load_state(&game_config_handle.handle) == Some(LoadState::Failed) {
+        error!("Could not load {GAME_CONFIG_PATH}, quitting");
+        app_exit_event_writer.send(AppExit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::game_config;
+
+    #[test]
+    fn shipped_config_is_valid() {
+        game_config().validate().unwrap();
+    }
+
+    #[test]
+    fn rejects_a_negative_ufo_cooldown() {
+        let mut config = game_config();
+        config.ufos.weapon.cooldown_seconds = -1.0;
+        let Err(GameConfigError::Invalid(problems)) = config.validate() else {
+            panic!("a negative ufos.weapon.cooldown_seconds should be invalid");
+        };
+        assert!(problems.contains("ufos.weapon.cooldown_seconds must not be negative"));
+    }
+}
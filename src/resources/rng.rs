@@ -0,0 +1,127 @@
+use std::env;
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+const SEED_ARG: &str = "--seed";
+const SEED_ENV_VAR: &str = "GAME_SEED";
+
+/// The single source of gameplay randomness.
+///
+/// Systems draw from this instead of `rand::thread_rng()`, so the same seed
+/// and the same inputs always play out the same run. That only holds while
+/// the systems drawing from it run in a fixed order, so any two of them in the
+/// same schedule must be ordered against each other with `.after(...)`.
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = match configured_seed() {
+            Some(seed) => seed,
+            None => rand::thread_rng().gen(),
+        };
+        info!("Gameplay RNG seed: {seed} (replay with {SEED_ARG} {seed} or {SEED_ENV_VAR}={seed})");
+        app.insert_resource(GameRng::from_seed(seed));
+    }
+}
+
+/// The seed given by `--seed <n>` on the command line, falling back to the
+/// `GAME_SEED` environment variable.
+fn configured_seed() -> Option<u64> {
+    let arg = env::args()
+        .skip_while(|arg| arg != SEED_ARG)
+        .nth(1)
+        .or_else(|| env::var(SEED_ENV_VAR).ok())?;
+
+    match arg.parse() {
+        Ok(seed) => Some(seed),
+        Err(error) => {
+            warn!("Ignoring invalid seed {arg:?}: {error}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        plugins::{
+            actions::{Action, ActionAxis, ActionState},
+            asteroids::Asteroid,
+            movement::Velocity,
+        },
+        state::GameState,
+        test_utils::{add_gameplay_plugins, enter_state, headless_app},
+    };
+
+    /// Ten seconds of play, long enough for shots, splits and bounces.
+    const STEPS: usize = 600;
+
+    /// Plays `STEPS` fixed steps from `seed` while firing and turning, and
+    /// returns where every asteroid ended up.
+    fn play(seed: u64) -> Vec<(Vec3, Vec3, Quat)> {
+        let mut app = headless_app();
+        add_gameplay_plugins(&mut app);
+        app.insert_resource(GameRng::from_seed(seed));
+        enter_state(&mut app, GameState::MainMenu);
+        enter_state(&mut app, GameState::InGame);
+
+        let mut action_state = app.world.resource_mut::<ActionState>();
+        action_state.press(Action::Fire);
+        action_state.set_axis(ActionAxis::Turn, 0.3);
+        action_state.set_axis(ActionAxis::Thrust, 0.5);
+        for _ in 0..STEPS {
+            app.update();
+        }
+
+        app.world
+            .query_filtered::<(&Transform, &Velocity), With<Asteroid>>()
+            .iter(&app.world)
+            .map(|(transform, velocity)| {
+                (transform.translation, velocity.value, transform.rotation)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_and_inputs_play_out_the_same() {
+        let asteroids = play(12);
+        assert!(!asteroids.is_empty());
+        assert_eq!(asteroids, play(12));
+        assert_ne!(asteroids, play(13));
+    }
+}
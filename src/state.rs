@@ -1,41 +1,133 @@
-use bevy::prelude::*;
+use bevy::{
+    ecs::schedule::{apply_state_transition, ScheduleLabel},
+    prelude::*,
+};
+
+use crate::plugins::actions::{Action, ActionState};
 
 #[derive(States, Debug, Hash, Eq, PartialEq, Clone, Default)]
 pub enum GameState {
+    /// Waiting for the game config to load.
     #[default]
+    Loading,
+    MainMenu,
     InGame,
     Paused,
     GameOver,
 }
 
+impl GameState {
+    /// Whether being in this state also counts as being in `other`: the
+    /// run is still underneath the pause menu and the game over screen. The
+    /// game over screen leaves clearing the run to `EndRun`.
+    fn is_within(&self, other: &GameState) -> bool {
+        self == other
+            || (matches!(self, GameState::Paused | GameState::GameOver)
+                && *other == GameState::InGame)
+    }
+}
+
+/// Despawns the entity and its children once the game leaves this state.
+#[derive(Component, Debug)]
+pub struct StateScoped(pub GameState);
+
+/// Runs whenever a fresh run begins, to spawn the player and reset per-run
+/// resources.
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct NewRun;
+
+/// Runs whenever a run is over or abandoned, to clear the world for the
+/// next one.
+#[derive(ScheduleLabel, Debug, Hash, PartialEq, Eq, Clone)]
+pub struct EndRun;
+
 pub struct StatePlugin;
 
 impl Plugin for StatePlugin {
     fn build(&self, app: &mut App) {
-        app.add_state::<GameState>().add_systems(
-            Update,
-            (
-                game_state_input_events,
-                transition_to_in_game.run_if(in_state(GameState::GameOver)),
-            ),
-        );
+        app.add_state::<GameState>()
+            .init_schedule(NewRun)
+            .init_schedule(EndRun)
+            .add_systems(
+                StateTransition,
+                despawn_state_scoped_entities.after(apply_state_transition::<GameState>),
+            )
+            .add_systems(Update, game_state_input_events)
+            .add_systems(EndRun, despawn_run_entities)
+            .add_systems(
+                OnTransition {
+                    from: GameState::MainMenu,
+                    to: GameState::InGame,
+                },
+                start_new_run,
+            )
+            .add_systems(OnExit(GameState::GameOver), end_run)
+            .add_systems(
+                OnTransition {
+                    from: GameState::Paused,
+                    to: GameState::MainMenu,
+                },
+                end_run,
+            )
+            .add_systems(
+                OnTransition {
+                    from: GameState::GameOver,
+                    to: GameState::InGame,
+                },
+                start_new_run,
+            );
     }
 }
 
 fn game_state_input_events(
     mut next_state: ResMut<NextState<GameState>>,
     state: Res<State<GameState>>,
-    keyboard_input: Res<Input<KeyCode>>,
+    action_state: Res<ActionState>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Escape) {
-        match state.get() {
-            GameState::InGame => next_state.set(GameState::Paused),
-            GameState::Paused => next_state.set(GameState::InGame),
-            _ => (),
+    // the pause menu handles unpausing
+    if action_state.just_pressed(Action::Pause) && *state.get() == GameState::InGame {
+        next_state.set(GameState::Paused);
+    }
+}
+
+fn despawn_state_scoped_entities(
+    mut commands: Commands,
+    mut previous_state: Local<Option<GameState>>,
+    state: Res<State<GameState>>,
+    query: Query<(Entity, &StateScoped)>,
+) {
+    let state = state.get();
+    let Some(exited) = previous_state.replace(state.clone()) else {
+        return;
+    };
+
+    for (entity, scope) in query.iter() {
+        if exited.is_within(&scope.0) && !state.is_within(&scope.0) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// Clears out the run even when the state doesn't change, such as when
+/// restarting from the pause menu.
+fn despawn_run_entities(mut commands: Commands, query: Query<(Entity, &StateScoped)>) {
+    for (entity, scope) in query.iter() {
+        if scope.0 == GameState::InGame {
+            commands.entity(entity).despawn_recursive();
         }
     }
 }
 
-fn transition_to_in_game(mut next_state: ResMut<NextState<GameState>>) {
-    next_state.set(GameState::InGame);
+fn start_new_run(world: &mut World) {
+    world.run_schedule(NewRun);
+}
+
+fn end_run(world: &mut World) {
+    world.run_schedule(EndRun);
+}
+
+/// Abandons the current run and starts a fresh one right away.
+pub fn restart_run(world: &mut World) {
+    end_run(world);
+    start_new_run(world);
 }
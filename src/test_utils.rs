@@ -0,0 +1,65 @@
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    plugins::{
+        actions::ActionState, asteroids::AsteroidPlugin,
+        collision_detection::CollisionDetectionPlugin, demage::DemagePlugin,
+        despawn::DespawnPlugin, homing::HomingPlugin, interpolation::InterpolationPlugin,
+        movement::MovementPlugin, physics::PhysicsPlugin, pickups::PickupPlugin,
+        playfield::PlayfieldPlugin, rotation::RotationPlugin, schedule::SchedulePlugin,
+        shield::ShieldPlugin, spaceship::SpaceshipPlugin, ufo::UfoPlugin, waves::WavesPlugin,
+        weapon::WeaponPlugin,
+    },
+    resources::{asset_loader::SceneAssets, game_config::GameConfig},
+    state::{GameState, StatePlugin},
+};
+
+/// The shipped config, read straight from the assets folder.
+pub fn game_config() -> GameConfig {
+    ron::from_str(include_str!("../assets/game.config.ron")).expect("game.config.ron should parse")
+}
+
+/// An app without a window, renderer or asset server, where every `update`
+/// after the first runs exactly one fixed step.
+pub fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(SchedulePlugin)
+        .add_plugins(StatePlugin)
+        .init_resource::<ActionState>()
+        .insert_resource(game_config());
+    let timestep = app.world.resource::<Time<Fixed>>().timestep();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+    app
+}
+
+/// Everything that plays a run, without menus, HUD, audio or scores.
+pub fn add_gameplay_plugins(app: &mut App) {
+    app.add_plugins((
+        SpaceshipPlugin,
+        AsteroidPlugin,
+        UfoPlugin,
+        PickupPlugin,
+        WavesPlugin,
+    ))
+    .add_plugins((
+        MovementPlugin,
+        InterpolationPlugin,
+        PlayfieldPlugin,
+        RotationPlugin,
+        CollisionDetectionPlugin,
+        DemagePlugin,
+        PhysicsPlugin,
+        ShieldPlugin,
+        WeaponPlugin,
+        HomingPlugin,
+        DespawnPlugin,
+    ))
+    .init_resource::<SceneAssets>();
+}
+
+/// Moves to `state`, running its transition schedules.
+pub fn enter_state(app: &mut App, state: GameState) {
+    app.world.resource_mut::<NextState<GameState>>().set(state);
+    app.update();
+}